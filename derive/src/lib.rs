@@ -0,0 +1,165 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Error, Fields, Index, Lit,
+    Meta, NestedMeta, Path, Result, Type,
+};
+
+/// Derives `bevy_ui_animation::Lerp` for a struct or a newtype.
+///
+/// Every field is interpolated with its own `Lerp` implementation unless one of the following
+/// field attributes is given:
+///
+/// - `#[lerp(skip)]`: Keeps the source value.
+/// - `#[lerp(snap = 0.5)]`: Jumps from the source value to the target value once the delta reaches
+///   the threshold. The threshold defaults to `1.0` when omitted.
+/// - `#[lerp(with = "path::to::lerp")]`: Uses a custom `fn(&T, &T, f32) -> T`.
+///
+/// `Lerp::accumulate` adds the changes of the interpolated fields, while the fields with an attribute keep
+/// their base value.
+#[proc_macro_derive(Lerp, attributes(lerp))]
+pub fn derive_lerp(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum Strategy {
+    Lerp,
+    Skip,
+    Snap(f32),
+    With(Path),
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "`Lerp` can only be derived for structs",
+            ))
+        }
+    };
+    let mut bounds: Vec<(Type, bool)> = Vec::new();
+    let mut values = Vec::new();
+    let mut accumulated = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let member = match field.ident {
+            Some(ref ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+        let ty = &field.ty;
+        let strategy = strategy(&field.attrs)?;
+        accumulated.push(match strategy {
+            Strategy::Lerp => quote! {
+                ::bevy_ui_animation::Lerp::accumulate(&self.#member, &from.#member, &to.#member)
+            },
+            _ => quote!(::core::clone::Clone::clone(&self.#member)),
+        });
+        let value = match strategy {
+            Strategy::Lerp => {
+                bounds.push((ty.clone(), true));
+                quote!(::bevy_ui_animation::Lerp::lerp(&self.#member, &target.#member, delta))
+            }
+            Strategy::Skip => {
+                bounds.push((ty.clone(), false));
+                quote!(::core::clone::Clone::clone(&self.#member))
+            }
+            Strategy::Snap(threshold) => {
+                bounds.push((ty.clone(), false));
+                quote! {
+                    if delta < #threshold {
+                        ::core::clone::Clone::clone(&self.#member)
+                    } else {
+                        ::core::clone::Clone::clone(&target.#member)
+                    }
+                }
+            }
+            Strategy::With(path) => {
+                bounds.push((ty.clone(), false));
+                quote!(#path(&self.#member, &target.#member, delta))
+            }
+        };
+        values.push((member, value));
+    }
+    let (members, values): (Vec<_>, Vec<_>) = values.into_iter().unzip();
+    let construct = |values: &[TokenStream2]| match fields {
+        Fields::Named(_) => quote!(Self { #(#members: #values),* }),
+        Fields::Unnamed(_) => quote!(Self(#(#values),*)),
+        Fields::Unit => quote!(Self),
+    };
+    let (body, accumulated) = (construct(&values), construct(&accumulated));
+
+    if !input.generics.params.is_empty() {
+        let where_clause = input.generics.make_where_clause();
+        for (ty, lerp) in bounds {
+            where_clause.predicates.push(if lerp {
+                parse_quote!(#ty: ::bevy_ui_animation::Lerp)
+            } else {
+                parse_quote!(#ty: ::core::clone::Clone)
+            });
+        }
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_ui_animation::Lerp for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn lerp(&self, target: &Self, delta: f32) -> Self {
+                #body
+            }
+            #[allow(unused_variables)]
+            fn accumulate(&self, from: &Self, to: &Self) -> Self {
+                #accumulated
+            }
+        }
+    })
+}
+
+fn strategy(attrs: &[syn::Attribute]) -> Result<Strategy> {
+    let mut strategy = Strategy::Lerp;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("lerp")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[lerp(...)]`")),
+        };
+        for nested in list.nested {
+            strategy = match nested {
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("skip") => Strategy::Skip,
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("snap") => {
+                    Strategy::Snap(1.0)
+                }
+                NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("snap") => {
+                    match nv.lit {
+                        Lit::Float(ref lit) => Strategy::Snap(lit.base10_parse()?),
+                        Lit::Int(ref lit) => Strategy::Snap(lit.base10_parse()?),
+                        ref lit => {
+                            return Err(Error::new(lit.span(), "expected a threshold number"))
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("with") => {
+                    match nv.lit {
+                        Lit::Str(ref lit) => Strategy::With(lit.parse()?),
+                        ref lit => return Err(Error::new(lit.span(), "expected a function path")),
+                    }
+                }
+                nested => {
+                    return Err(Error::new(
+                        nested.span(),
+                        "expected `skip`, `snap`, `snap = <threshold>` or `with = \"<path>\"`",
+                    ))
+                }
+            };
+        }
+    }
+    Ok(strategy)
+}
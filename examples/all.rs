@@ -29,12 +29,12 @@ fn setup(mut commands: Commands) {
             ..Default::default()
         })
         .insert(Animation::new(Vars {
-            style: Some(Style {
-                size: Size::new(Val::Percent(50.0), Val::Percent(50.0)),
+            style: Some(StyleTarget {
+                size: Some(Size::new(Val::Percent(50.0), Val::Percent(50.0))),
                 ..Default::default()
             }),
             color: Some(UiColor(Color::RED)),
-            transform: Some(Transform::from_rotation(Quat::from_rotation_z(
+            transform: Some(TransformTarget::from_rotation(Quat::from_rotation_z(
                 180_f32.to_radians(),
             ))),
             duration: 2.0,
@@ -47,7 +47,8 @@ fn setup(mut commands: Commands, assets: Res<AssetServer>) {
                 .insert(Animation::new(Vars {
                     text_color: Some(TextColor {
                         target: Color::RED,
-                        section: 0,
+                        sections: TextSections::from(0),
+                        ..Default::default()
                     }),
                     duration: 2.0,
                     repeat: true,
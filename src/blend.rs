@@ -0,0 +1,212 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bevy::prelude::*;
+
+use crate::lerp::Lerp;
+
+/// How the value of an animation combines with the ones written by other animations.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Blend {
+    /// Overwrites the value, the last animation to write it wins.
+    #[default]
+    Replace,
+    /// Adds the offset from its own source onto the base value of the property, so several animations sum up.
+    Additive,
+}
+
+/// A new identifier for an animation adding its offset onto properties.
+pub(crate) fn next_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A property additive animations sum onto.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+enum Property {
+    /// Shared by every animation using the same name.
+    Named(&'static str),
+    /// Owned by a single animation.
+    Unnamed(u64),
+}
+
+/// The base value of a property and the offsets of the animations adding onto it.
+struct Stack<T> {
+    base: T,
+    /// The value written last, a different one meaning another writer replaced the base.
+    written: T,
+    /// The animation, its source, its current value and the frame it was last added in.
+    offsets: Vec<(u64, T, T, u64)>,
+}
+impl<T: Lerp + Clone + PartialEq> Stack<T> {
+    /// Takes `live` as the new base if it isn't the value written last.
+    fn sync(&mut self, live: T) {
+        if live != self.written {
+            self.base = live.clone();
+            self.written = live;
+        }
+    }
+}
+
+/// A [`Stack`] of any value type.
+trait AnyStack: Send + Sync {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// Marks the offset of the animation `id` as added in `frame`.
+    fn hold(&mut self, id: u64, frame: u64);
+    /// Bakes the offsets not added in `frame` into the base, returning whether any offset is left.
+    fn retain(&mut self, frame: u64) -> bool;
+}
+impl<T: Lerp + Clone + PartialEq + Send + Sync + 'static> AnyStack for Stack<T> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn hold(&mut self, id: u64, frame: u64) {
+        for offset in self.offsets.iter_mut().filter(|offset| offset.0 == id) {
+            offset.3 = frame;
+        }
+    }
+    fn retain(&mut self, frame: u64) -> bool {
+        let base = &mut self.base;
+        self.offsets.retain(|(_, from, to, added)| {
+            if *added != frame {
+                *base = base.accumulate(from, to);
+            }
+            *added == frame
+        });
+        !self.offsets.is_empty()
+    }
+}
+
+/// The base values of the properties animated with [`Blend::Additive`], and the offsets added onto them.
+///
+/// Inserted by [`AnimationPlugin`](crate::AnimationPlugin) and used by every [`tween_system`](crate::tween_system).
+/// Lenses reach the properties of the component they animate through a [`Layer`].
+#[derive(Default)]
+pub struct Layers {
+    frame: u64,
+    stacks: HashMap<(Entity, TypeId, TypeId, Property), Box<dyn AnyStack>>,
+}
+impl Layers {
+    /// Keeps the offsets of the animation `id` on the properties of `entity`, while it's paused for instance.
+    pub(crate) fn hold(&mut self, entity: Entity, id: u64) {
+        let frame = self.frame;
+        for (_, stack) in self.stacks.iter_mut().filter(|(key, _)| key.0 == entity) {
+            stack.hold(id, frame);
+        }
+    }
+}
+
+/// The properties of a component of an entity, as seen by one additive animation.
+///
+/// Passed to [`Lens::accumulate`](crate::Lens::accumulate), a lens adds its offset through it:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ui_animation::*;
+/// struct Width {
+///     source: Val,
+///     target: Val,
+///     base: Option<Val>,
+/// }
+/// impl Lens<Style> for Width {
+///     fn capture(&mut self, style: &Style) {
+///         self.source = style.size.width;
+///     }
+///     fn apply(&self, style: &mut Style, delta: f32) {
+///         style.size.width = self.source.lerp(&self.target, delta);
+///     }
+///     fn accumulate(&mut self, style: &mut Style, layer: &mut Layer, delta: f32) {
+///         let live = style.size.width;
+///         let base = *self.base.get_or_insert_with(|| layer.base(Some("width"), live));
+///         let value = base.lerp(&self.target, delta);
+///         style.size.width = layer.add(Some("width"), live, &base, &value);
+///     }
+/// }
+/// ```
+pub struct Layer<'a> {
+    layers: &'a mut Layers,
+    entity: Entity,
+    component: TypeId,
+    id: u64,
+}
+impl<'a> Layer<'a> {
+    /// The properties of the component `C` of `entity`, for the animation `id`.
+    pub(crate) fn new<C: 'static>(layers: &'a mut Layers, entity: Entity, id: u64) -> Self {
+        Self {
+            layers,
+            entity,
+            component: TypeId::of::<C>(),
+            id,
+        }
+    }
+    fn stack<T>(&mut self, property: Option<&'static str>, live: &T) -> &mut Stack<T>
+    where
+        T: Lerp + Clone + PartialEq + Send + Sync + 'static,
+    {
+        let property = property.map_or(Property::Unnamed(self.id), Property::Named);
+        let key = (self.entity, self.component, TypeId::of::<T>(), property);
+        self.layers
+            .stacks
+            .entry(key)
+            .or_insert_with(|| {
+                Box::new(Stack {
+                    base: live.clone(),
+                    written: live.clone(),
+                    offsets: Vec::new(),
+                })
+            })
+            .as_any_mut()
+            .downcast_mut()
+            .expect("stacks are keyed by their value type")
+    }
+    /// The base value of `property`, `live` being the current value of the property.
+    ///
+    /// `live` becomes the base value if it isn't the value written last, as another animation replaced it.
+    /// Properties named the same way are shared by the additive animations of the component, while a `None`
+    /// property belongs to the animation alone.
+    pub fn base<T>(&mut self, property: Option<&'static str>, live: T) -> T
+    where
+        T: Lerp + Clone + PartialEq + Send + Sync + 'static,
+    {
+        let stack = self.stack(property, &live);
+        stack.sync(live);
+        stack.base.clone()
+    }
+    /// Sets the offset of the animation to the change from `from` to `to`, and returns the base value of
+    /// `property` with the offsets of every animation added, to be written to the component.
+    ///
+    /// Call it every frame the animation plays, an offset not added during a frame being merged into the base.
+    pub fn add<T>(&mut self, property: Option<&'static str>, live: T, from: &T, to: &T) -> T
+    where
+        T: Lerp + Clone + PartialEq + Send + Sync + 'static,
+    {
+        let (id, frame) = (self.id, self.layers.frame);
+        let stack = self.stack(property, &live);
+        stack.sync(live);
+        let offset = (id, from.clone(), to.clone(), frame);
+        match stack.offsets.iter_mut().find(|offset| offset.0 == id) {
+            Some(current) => *current = offset,
+            None => stack.offsets.push(offset),
+        }
+        let value = stack
+            .offsets
+            .iter()
+            .fold(stack.base.clone(), |value, (_, from, to, _)| {
+                value.accumulate(from, to)
+            });
+        stack.written = value.clone();
+        value
+    }
+}
+
+/// Bakes the offsets of the animations that stopped adding them into the base values,
+/// and forgets the properties nothing adds onto anymore.
+pub(crate) fn layer_system(mut layers: ResMut<Layers>) {
+    let layers = &mut *layers;
+    let frame = layers.frame;
+    layers.stacks.retain(|_, stack| stack.retain(frame));
+    layers.frame += 1;
+}
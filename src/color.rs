@@ -0,0 +1,390 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::{lerp::Lerp, text::TextSections};
+
+/// The color space colors are interpolated in.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ColorSpace {
+    /// Gamma-encoded sRGB components.
+    Srgb,
+    /// Linear RGB components.
+    #[default]
+    LinearRgb,
+    /// Hue, saturation and lightness, going around the hue wheel along the given path.
+    Hsl(HuePath),
+    /// The perceptual Oklab space.
+    Oklab,
+    /// The polar form of Oklab, going around the hue wheel along the given path.
+    Oklch(HuePath),
+}
+
+/// The direction to go around the hue wheel.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HuePath {
+    Shortest,
+    Longest,
+}
+
+/// A color gradient whose stops are positioned from `0.0` to `1.0` along the animation.
+///
+/// Drives the colors of the [`UiColor`], [`Sprite`] and [`TextureAtlasSprite`] components,
+/// and of the selected [`Text`] sections.
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+    /// Sections of the [`Text`] component to animate.
+    pub sections: TextSections,
+}
+impl Gradient {
+    /// Create a gradient from `(position, color)` stops.
+    pub fn new(stops: impl IntoIterator<Item = (f32, Color)>) -> Self {
+        let mut stops: Vec<(f32, Color)> = stops.into_iter().collect();
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Self {
+            stops,
+            sections: TextSections::All,
+        }
+    }
+    /// Only animates the given sections of the [`Text`] component.
+    pub fn with_sections(mut self, sections: impl Into<TextSections>) -> Self {
+        self.sections = sections.into();
+        self
+    }
+    /// Create a gradient of evenly spaced colors.
+    pub fn even(colors: impl IntoIterator<Item = Color>) -> Self {
+        let colors: Vec<Color> = colors.into_iter().collect();
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        Self::new(
+            colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| (i as f32 / last, color)),
+        )
+    }
+    /// Samples the gradient at `position`, interpolating between the surrounding stops in the given color space.
+    pub(crate) fn sample(&self, position: f32, space: ColorSpace) -> Option<Color> {
+        let (first, last) = (self.stops.first()?, self.stops.last()?);
+        if position <= first.0 {
+            return Some(first.1);
+        }
+        if position >= last.0 {
+            return Some(last.1);
+        }
+        let next = self.stops.iter().position(|stop| stop.0 > position)?;
+        let (from, to) = (self.stops[next - 1], self.stops[next]);
+        Some(lerp(
+            from.1,
+            to.1,
+            (position - from.0) / (to.0 - from.0),
+            space,
+        ))
+    }
+    /// Writes the color sampled at `position` to every component the entity has.
+    pub(crate) fn apply(
+        &self,
+        position: f32,
+        space: ColorSpace,
+        color: Option<&mut UiColor>,
+        text: Option<&mut Text>,
+        sprite: Option<&mut Sprite>,
+        atlas: Option<&mut TextureAtlasSprite>,
+    ) {
+        let value = match self.sample(position, space) {
+            Some(value) => value,
+            None => return,
+        };
+        if let Some(color) = color {
+            color.0 = value;
+        }
+        if let Some(text) = text {
+            for index in self.sections.indices(text.sections.len()) {
+                text.sections[index].style.color = value;
+            }
+        }
+        if let Some(sprite) = sprite {
+            sprite.color = value;
+        }
+        if let Some(atlas) = atlas {
+            atlas.color = value;
+        }
+    }
+}
+
+/// Interpolates from `source` to `target` in the given color space.
+pub(crate) fn lerp(source: Color, target: Color, delta: f32, space: ColorSpace) -> Color {
+    let alpha = source.a().lerp(&target.a(), delta);
+    match space {
+        ColorSpace::Srgb => {
+            let [r, g, b] = lerp3(srgb(source), srgb(target), delta);
+            Color::rgba(r, g, b, alpha)
+        }
+        ColorSpace::LinearRgb => {
+            let [r, g, b] = lerp3(linear(source), linear(target), delta);
+            Color::rgba_linear(r, g, b, alpha)
+        }
+        ColorSpace::Hsl(path) => {
+            let [source_h, source_s, source_l] = srgb_to_hsl(srgb(source));
+            let [target_h, target_s, target_l] = srgb_to_hsl(srgb(target));
+            let [r, g, b] = hsl_to_srgb([
+                lerp_hue(source_h, target_h, source_s, target_s, delta, path),
+                source_s.lerp(&target_s, delta),
+                source_l.lerp(&target_l, delta),
+            ]);
+            Color::rgba(r, g, b, alpha)
+        }
+        ColorSpace::Oklab => {
+            let [r, g, b] = oklab_to_linear(lerp3(
+                linear_to_oklab(linear(source)),
+                linear_to_oklab(linear(target)),
+                delta,
+            ));
+            Color::rgba_linear(r, g, b, alpha)
+        }
+        ColorSpace::Oklch(path) => {
+            let [source_l, source_c, source_h] = oklab_to_oklch(linear_to_oklab(linear(source)));
+            let [target_l, target_c, target_h] = oklab_to_oklch(linear_to_oklab(linear(target)));
+            let [r, g, b] = oklab_to_linear(oklch_to_oklab([
+                source_l.lerp(&target_l, delta),
+                source_c.lerp(&target_c, delta),
+                lerp_hue(source_h, target_h, source_c, target_c, delta, path),
+            ]));
+            Color::rgba_linear(r, g, b, alpha)
+        }
+    }
+}
+
+fn lerp3(source: [f32; 3], target: [f32; 3], delta: f32) -> [f32; 3] {
+    [
+        source[0].lerp(&target[0], delta),
+        source[1].lerp(&target[1], delta),
+        source[2].lerp(&target[2], delta),
+    ]
+}
+
+/// Interpolates hues in degrees, keeping the hue of the other end for achromatic colors.
+fn lerp_hue(
+    source: f32,
+    target: f32,
+    source_chroma: f32,
+    target_chroma: f32,
+    delta: f32,
+    path: HuePath,
+) -> f32 {
+    let source = if source_chroma < 1e-4 { target } else { source };
+    let target = if target_chroma < 1e-4 { source } else { target };
+    let mut diff = target - source;
+    match path {
+        HuePath::Shortest if diff > 180.0 => diff -= 360.0,
+        HuePath::Shortest if diff < -180.0 => diff += 360.0,
+        HuePath::Longest if diff > 0.0 && diff < 180.0 => diff -= 360.0,
+        HuePath::Longest if diff < 0.0 && diff > -180.0 => diff += 360.0,
+        _ => {}
+    }
+    (source + diff * delta).rem_euclid(360.0)
+}
+
+fn srgb(color: Color) -> [f32; 3] {
+    let [r, g, b, _] = color.as_rgba_f32();
+    [r, g, b]
+}
+
+fn linear(color: Color) -> [f32; 3] {
+    let [r, g, b, _] = color.as_linear_rgba_f32();
+    [r, g, b]
+}
+
+fn srgb_to_hsl([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let chroma = max - min;
+    if chroma == 0.0 {
+        return [0.0, 0.0, lightness];
+    }
+    let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    [hue, saturation, lightness]
+}
+
+fn hsl_to_srgb([hue, saturation, lightness]: [f32; 3]) -> [f32; 3] {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    [r + m, g + m, b + m]
+}
+
+// Reference: https://bottosson.github.io/posts/oklab
+fn linear_to_oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.41222146 * r + 0.53633255 * g + 0.051445995 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+    let s = (0.08830246 * r + 0.28171885 * g + 0.6299787 * b).cbrt();
+    [
+        0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+    ]
+}
+
+fn oklab_to_linear([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = (l + 0.39633778 * a + 0.21580376 * b).powi(3);
+    let m_ = (l - 0.105561346 * a - 0.06385417 * b).powi(3);
+    let s_ = (l - 0.08948418 * a - 1.2914855 * b).powi(3);
+    [
+        4.0767417 * l_ - 3.3077116 * m_ + 0.23096994 * s_,
+        -1.268438 * l_ + 2.6097574 * m_ - 0.34131938 * s_,
+        -0.0041960864 * l_ - 0.7034186 * m_ + 1.7076147 * s_,
+    ]
+}
+
+fn oklab_to_oklch([l, a, b]: [f32; 3]) -> [f32; 3] {
+    [l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0)]
+}
+
+fn oklch_to_oklab([l, c, h]: [f32; 3]) -> [f32; 3] {
+    let (sin, cos) = h.to_radians().sin_cos();
+    [l, c * cos, c * sin]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color(color: Color, expected: Color) {
+        let color = Vec4::from(color.as_rgba_f32());
+        let expected = Vec4::from(expected.as_rgba_f32());
+        assert!(
+            color.abs_diff_eq(expected, 1e-3),
+            "{:?} != {:?}",
+            color,
+            expected
+        );
+    }
+
+    #[test]
+    fn rgb() {
+        let source = Color::rgba(1.0, 0.0, 0.0, 1.0);
+        let target = Color::rgba(0.0, 1.0, 0.0, 0.0);
+
+        assert_color(
+            lerp(source, target, 0.5, ColorSpace::Srgb),
+            Color::rgba(0.5, 0.5, 0.0, 0.5),
+        );
+        assert_color(
+            lerp(source, target, 0.5, ColorSpace::LinearRgb),
+            Color::rgba_linear(0.5, 0.5, 0.0, 0.5),
+        );
+        assert_color(lerp(source, target, 1.0, ColorSpace::LinearRgb), target);
+    }
+
+    #[test]
+    fn hsl() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+
+        assert_color(
+            lerp(red, green, 0.5, ColorSpace::Hsl(HuePath::Shortest)),
+            Color::rgb(1.0, 1.0, 0.0),
+        );
+        assert_color(
+            lerp(red, green, 0.5, ColorSpace::Hsl(HuePath::Longest)),
+            Color::rgb(0.0, 0.0, 1.0),
+        );
+        assert_color(
+            lerp(Color::GRAY, green, 0.0, ColorSpace::Hsl(HuePath::Shortest)),
+            Color::GRAY,
+        );
+        assert_color(
+            lerp(red, green, 1.0, ColorSpace::Hsl(HuePath::Longest)),
+            green,
+        );
+    }
+
+    #[test]
+    fn oklab() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+
+        for space in [
+            ColorSpace::Oklab,
+            ColorSpace::Oklch(HuePath::Shortest),
+            ColorSpace::Oklch(HuePath::Longest),
+        ] {
+            assert_color(lerp(red, green, 0.0, space), red);
+            assert_color(lerp(red, green, 1.0, space), green);
+        }
+        assert_color(
+            lerp(
+                Color::WHITE,
+                Color::BLACK,
+                1.0,
+                ColorSpace::Oklch(HuePath::Shortest),
+            ),
+            Color::BLACK,
+        );
+    }
+
+    #[test]
+    fn gradient() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let green = Color::rgb(0.0, 1.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let gradient = Gradient::new([(1.0, blue), (0.0, red), (0.25, green)]);
+
+        assert_color(gradient.sample(-0.5, ColorSpace::Srgb).unwrap(), red);
+        assert_color(gradient.sample(0.0, ColorSpace::Srgb).unwrap(), red);
+        assert_color(
+            gradient.sample(0.125, ColorSpace::Srgb).unwrap(),
+            Color::rgb(0.5, 0.5, 0.0),
+        );
+        assert_color(gradient.sample(0.25, ColorSpace::Srgb).unwrap(), green);
+        assert_color(
+            gradient.sample(0.625, ColorSpace::Srgb).unwrap(),
+            Color::rgb(0.0, 0.5, 0.5),
+        );
+        assert_color(gradient.sample(1.5, ColorSpace::Srgb).unwrap(), blue);
+
+        let even = Gradient::even([red, green, blue]);
+        assert_color(even.sample(0.5, ColorSpace::Srgb).unwrap(), green);
+        assert!(Gradient::new([]).sample(0.5, ColorSpace::Srgb).is_none());
+    }
+
+    #[test]
+    fn gradient_sections() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let gradient = Gradient::even([red, red]).with_sections(1);
+        let mut text = Text {
+            sections: vec![TextSection::default(); 3],
+            ..Default::default()
+        };
+        let mut atlas = TextureAtlasSprite::default();
+        gradient.apply(
+            0.5,
+            ColorSpace::Srgb,
+            None,
+            Some(&mut text),
+            None,
+            Some(&mut atlas),
+        );
+        assert_color(text.sections[0].style.color, Color::WHITE);
+        assert_color(text.sections[1].style.color, red);
+        assert_color(text.sections[2].style.color, Color::WHITE);
+        assert_color(atlas.color, red);
+    }
+}
@@ -8,3 +8,10 @@ pub struct CompleteEvent {
     /// The [`Entity`] the animation which completed is attached to.
     pub entity: Entity,
 }
+
+/// Event raised when a [`TextReveal`](crate::TextReveal) finished revealing its text.
+#[derive(Copy, Clone)]
+pub struct TextRevealEvent {
+    /// The [`Entity`] the text being revealed is attached to.
+    pub entity: Entity,
+}
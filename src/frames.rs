@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+/// Swaps the [`UiImage`] of an entity through a sequence of images.
+pub struct Frames {
+    /// Images of the sequence.
+    pub images: Vec<Handle<Image>>,
+    /// How long each image is shown.
+    pub timing: FrameTiming,
+}
+impl Frames {
+    /// Plays `images` at `fps` frames per second.
+    pub fn fps(images: Vec<Handle<Image>>, fps: f32) -> Self {
+        Self {
+            images,
+            timing: FrameTiming::Fps(fps),
+        }
+    }
+    /// Plays `images`, each one for the seconds at the same index in `durations`.
+    pub fn durations(images: Vec<Handle<Image>>, durations: Vec<f32>) -> Self {
+        Self {
+            images,
+            timing: FrameTiming::Durations(durations),
+        }
+    }
+    /// The duration of the whole sequence.
+    pub(crate) fn duration(&self) -> f32 {
+        match self.timing {
+            FrameTiming::Fps(fps) if fps > 0.0 => self.images.len() as f32 / fps,
+            FrameTiming::Fps(_) => 0.0,
+            FrameTiming::Durations(ref durations) => durations
+                .iter()
+                .take(self.images.len())
+                .map(|duration| duration.max(0.0))
+                .sum(),
+        }
+    }
+    /// The index of the image shown at `progress`.
+    pub(crate) fn index(&self, progress: f32) -> Option<usize> {
+        let last = self.images.len().checked_sub(1)?;
+        let elapsed = progress.clamp(0.0, 1.0) * self.duration();
+        let index = match self.timing {
+            FrameTiming::Fps(fps) => (elapsed * fps.max(0.0)) as usize,
+            FrameTiming::Durations(ref durations) => {
+                let mut end = 0.0;
+                durations
+                    .iter()
+                    .position(|duration| {
+                        end += duration.max(0.0);
+                        elapsed < end
+                    })
+                    .unwrap_or(last)
+            }
+        };
+        Some(index.min(last))
+    }
+    /// Swaps in the image shown at `progress`.
+    pub(crate) fn apply(&self, image: &mut UiImage, progress: f32) {
+        if let Some(index) = self.index(progress) {
+            if image.0 != self.images[index] {
+                image.0 = self.images[index].clone();
+            }
+        }
+    }
+}
+
+/// How long each image of [`Frames`] is shown.
+pub enum FrameTiming {
+    /// Every image for the same time, at the given frames per second.
+    Fps(f32),
+    /// Each image for the seconds at the same index.
+    Durations(Vec<f32>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames() {
+        let images = vec![Handle::default(); 4];
+        let frames = Frames::fps(images.clone(), 8.0);
+        assert_eq!(frames.duration(), 0.5);
+        assert_eq!(frames.index(0.0), Some(0));
+        assert_eq!(frames.index(0.3), Some(1));
+        assert_eq!(frames.index(1.0), Some(3));
+
+        let frames = Frames::durations(images, vec![0.5, 0.25, 0.25, 1.0]);
+        assert_eq!(frames.duration(), 2.0);
+        assert_eq!(frames.index(0.2), Some(0));
+        assert_eq!(frames.index(0.25), Some(1));
+        assert_eq!(frames.index(0.45), Some(2));
+        assert_eq!(frames.index(1.0), Some(3));
+
+        let frames = Frames::durations(vec![Handle::default(); 2], vec![1.0]);
+        assert_eq!(frames.index(1.0), Some(1));
+        assert_eq!(Frames::fps(Vec::new(), 8.0).index(0.5), None);
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+/// Multiplies the alpha of the [`UiColor`] and [`Text`] of an entity and of all its descendants.
+///
+/// Nested groups combine, and the original colors are restored once every enclosing group is back to `1.0`.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct GroupOpacity(pub f32);
+impl Default for GroupOpacity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The alpha values of an entity before any group opacity was applied.
+struct Alphas {
+    color: Option<f32>,
+    sections: Vec<f32>,
+}
+
+type Targets<'a> = (Option<&'a mut UiColor>, Option<&'a mut Text>);
+
+pub(crate) fn group_opacity_system(
+    mut originals: Local<HashMap<Entity, Alphas>>,
+    groups: Query<(Entity, &GroupOpacity)>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    mut query: Query<Targets, Or<(With<UiColor>, With<Text>)>>,
+) {
+    if groups.iter().next().is_none() && originals.is_empty() {
+        return;
+    }
+    let mut factors = HashMap::new();
+    for (entity, _) in groups.iter() {
+        let mut ancestor = parents.get(entity).ok().map(|parent| parent.0);
+        let mut nested = false;
+        while let Some(current) = ancestor {
+            nested |= groups.get(current).is_ok();
+            ancestor = parents.get(current).ok().map(|parent| parent.0);
+        }
+        // Nested groups are reached from the outermost one.
+        if !nested {
+            descend(entity, 1.0, &groups, &children, &mut factors);
+        }
+    }
+    for (&entity, &factor) in factors.iter().filter(|(_, factor)| **factor < 1.0) {
+        if let Ok((mut color, mut text)) = query.get_mut(entity) {
+            let original = originals.entry(entity).or_insert_with(|| Alphas {
+                color: color.as_ref().map(|color| color.0.a()),
+                sections: text.as_ref().map_or_else(Vec::new, |text| {
+                    text.sections
+                        .iter()
+                        .map(|section| section.style.color.a())
+                        .collect()
+                }),
+            });
+            if let (Some(color), Some(alpha)) = (color.as_mut(), original.color) {
+                color.0.set_a(alpha * factor.max(0.0));
+            }
+            if let Some(text) = text.as_mut() {
+                for (section, alpha) in text.sections.iter_mut().zip(&original.sections) {
+                    section.style.color.set_a(alpha * factor.max(0.0));
+                }
+            }
+        }
+    }
+    originals.retain(|entity, original| {
+        if matches!(factors.get(entity), Some(factor) if *factor < 1.0) {
+            return true;
+        }
+        if let Ok((mut color, mut text)) = query.get_mut(*entity) {
+            if let (Some(color), Some(alpha)) = (color.as_mut(), original.color) {
+                color.0.set_a(alpha);
+            }
+            if let Some(text) = text.as_mut() {
+                for (section, alpha) in text.sections.iter_mut().zip(&original.sections) {
+                    section.style.color.set_a(*alpha);
+                }
+            }
+        }
+        false
+    });
+}
+
+/// Multiplies the factor of `entity` by its group opacity and passes it down to its children.
+fn descend(
+    entity: Entity,
+    factor: f32,
+    groups: &Query<(Entity, &GroupOpacity)>,
+    children: &Query<&Children>,
+    factors: &mut HashMap<Entity, f32>,
+) {
+    let factor = factor * groups.get(entity).map_or(1.0, |(_, group)| group.0);
+    factors.insert(entity, factor);
+    if let Ok(entity_children) = children.get(entity) {
+        for &child in entity_children.iter() {
+            descend(child, factor, groups, children, factors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alpha(world: &World, entity: Entity) -> f32 {
+        world.get::<UiColor>(entity).unwrap().0.a()
+    }
+
+    #[test]
+    fn nested() {
+        let mut world = World::default();
+        let mut stage = SystemStage::single(group_opacity_system);
+        let leaf = world
+            .spawn()
+            .insert(UiColor(Color::rgba(1.0, 1.0, 1.0, 0.8)))
+            .id();
+        let inner = world
+            .spawn()
+            .insert_bundle((GroupOpacity(0.5), UiColor(Color::WHITE)))
+            .push_children(&[leaf])
+            .id();
+        let outer = world
+            .spawn()
+            .insert(GroupOpacity(0.5))
+            .push_children(&[inner])
+            .id();
+        stage.run(&mut world);
+        assert_eq!(alpha(&world, inner), 0.25);
+        assert_eq!(alpha(&world, leaf), 0.2);
+        assert!(world.get::<UiColor>(outer).is_none());
+    }
+
+    #[test]
+    fn descendants() {
+        let mut world = World::default();
+        let mut stage = SystemStage::single(group_opacity_system);
+        let text = Text::with_section("", TextStyle::default(), TextAlignment::default());
+        let grandchild = world.spawn().insert(text).id();
+        let child = world.spawn().push_children(&[grandchild]).id();
+        world
+            .spawn()
+            .insert_bundle((GroupOpacity(0.5), UiColor(Color::WHITE)))
+            .push_children(&[child]);
+        stage.run(&mut world);
+        let text = world.get::<Text>(grandchild).unwrap();
+        assert_eq!(text.sections[0].style.color.a(), 0.5);
+    }
+
+    #[test]
+    fn restore() {
+        let mut world = World::default();
+        let mut stage = SystemStage::single(group_opacity_system);
+        let child = world
+            .spawn()
+            .insert(UiColor(Color::rgba(1.0, 1.0, 1.0, 0.8)))
+            .id();
+        let group = world
+            .spawn()
+            .insert_bundle((GroupOpacity(0.5), UiColor(Color::WHITE)))
+            .push_children(&[child])
+            .id();
+        stage.run(&mut world);
+        assert_eq!(alpha(&world, child), 0.4);
+        world.get_mut::<GroupOpacity>(group).unwrap().0 = 0.25;
+        stage.run(&mut world);
+        assert_eq!(alpha(&world, child), 0.2);
+        world.get_mut::<GroupOpacity>(group).unwrap().0 = 1.0;
+        stage.run(&mut world);
+        assert_eq!(alpha(&world, group), 1.0);
+        assert_eq!(alpha(&world, child), 0.8);
+    }
+}
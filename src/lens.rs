@@ -0,0 +1,250 @@
+use bevy::{ecs::component::TableStorage, prelude::*};
+
+use crate::{
+    blend::{next_id, Layer, Layers},
+    lerp::Lerp,
+    Blend, Ease, Playhead, Vars,
+};
+
+/// Accessor to a single property of a component.
+///
+/// A lens lets several [`Tween`]s animate different fields of the same component independently.
+pub trait Lens<C>: Send + Sync + 'static {
+    /// Captures the current value of the property as the source of the tween.
+    fn capture(&mut self, component: &C);
+    /// Writes the value interpolated from the captured source to the target.
+    fn apply(&self, component: &mut C, delta: f32);
+    /// Adds the change from the base value of the property to the value at `delta` onto the base in `layer`.
+    ///
+    /// Used by [`Blend::Additive`], see [`Layer`] to implement it. By default, the value is written like
+    /// [`Lens::apply`] does.
+    fn accumulate(&mut self, component: &mut C, layer: &mut Layer, delta: f32) {
+        let _ = layer;
+        self.apply(component, delta);
+    }
+}
+
+/// A [`Lens`] built from a getter and a setter.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ui_animation::*;
+/// let width = FieldLens::new(
+///     |style: &Style| style.size.width,
+///     |style: &mut Style, width| style.size.width = width,
+///     Val::Px(300.0),
+/// );
+/// ```
+pub struct FieldLens<C, T> {
+    get: Box<dyn Fn(&C) -> T + Send + Sync>,
+    set: Box<dyn Fn(&mut C, T) + Send + Sync>,
+    name: Option<&'static str>,
+    source: Option<T>,
+    /// The base value of the property when an additive tween started, its offset being measured from it.
+    base: Option<T>,
+    target: T,
+}
+impl<C, T> FieldLens<C, T> {
+    /// Create a new lens animating the property read by `get` and written by `set` to `target`.
+    pub fn new(
+        get: impl Fn(&C) -> T + Send + Sync + 'static,
+        set: impl Fn(&mut C, T) + Send + Sync + 'static,
+        target: T,
+    ) -> Self {
+        Self {
+            get: Box::new(get),
+            set: Box::new(set),
+            name: None,
+            source: None,
+            base: None,
+            target,
+        }
+    }
+    /// Names the property, so the additive tweens of a component using the same name sum onto one base value.
+    ///
+    /// Unnamed additive tweens each keep their own base value. The transform channels of an additive
+    /// [`Animation`](crate::Animation) add onto the ones of `"translation"`, `"rotation"` and `"scale"` they write.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+}
+impl<C, T> Lens<C> for FieldLens<C, T>
+where
+    C: 'static,
+    T: Lerp + Clone + PartialEq + Send + Sync + 'static,
+{
+    fn capture(&mut self, component: &C) {
+        self.source = Some((self.get)(component));
+    }
+    fn apply(&self, component: &mut C, delta: f32) {
+        if let Some(ref source) = self.source {
+            (self.set)(component, source.lerp(&self.target, delta));
+        }
+    }
+    fn accumulate(&mut self, component: &mut C, layer: &mut Layer, delta: f32) {
+        let live = (self.get)(component);
+        let name = self.name;
+        let base = self
+            .base
+            .get_or_insert_with(|| layer.base(name, live.clone()));
+        let value = base.lerp(&self.target, delta);
+        (self.set)(component, layer.add(name, live, base, &value));
+    }
+}
+
+/// Animates a single property of a component through a [`Lens`].
+pub struct Tween<C> {
+    lens: Box<dyn Lens<C>>,
+    pub(crate) playhead: Playhead,
+    pub(crate) ease: Ease,
+    pub(crate) repeat: bool,
+    pub(crate) yoyo: bool,
+    pub(crate) paused: bool,
+    pub(crate) captured: bool,
+    pub(crate) blend: Blend,
+    pub(crate) id: u64,
+}
+impl<C: 'static> Tween<C> {
+    /// Create a new tween.
+    ///
+    /// Only the timing fields of [`Vars`] are used: `delay`, `duration`, `ease`, `repeat`, `yoyo` and `paused`,
+    /// along with `blend`.
+    pub fn new(lens: impl Lens<C>, vars: Vars) -> Self {
+        Self {
+            lens: Box::new(lens),
+            playhead: Playhead::new(&vars),
+            ease: vars.ease,
+            repeat: vars.repeat,
+            yoyo: vars.yoyo,
+            paused: vars.paused,
+            captured: false,
+            blend: vars.blend,
+            id: next_id(),
+        }
+    }
+    /// Gets the tween's paused state which indicates whether or not the tween is currently paused.
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+    /// Pauses the instance.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+    /// Begins playing forward from wherever the playhead currently is.
+    pub fn play(&mut self) {
+        self.paused = false;
+    }
+    pub(crate) fn capture(&mut self, component: &C) {
+        self.lens.capture(component);
+        self.captured = true;
+    }
+    pub(crate) fn apply(
+        &mut self,
+        component: &mut C,
+        layers: &mut Layers,
+        entity: Entity,
+        delta: f32,
+    ) {
+        match self.blend {
+            Blend::Replace => self.lens.apply(component, delta),
+            Blend::Additive => {
+                let mut layer = Layer::new::<C>(layers, entity, self.id);
+                self.lens.accumulate(component, &mut layer, delta);
+            }
+        }
+    }
+}
+
+/// A set of [`Tween`]s animating the properties of the component `C`.
+///
+/// The component is removed once every tween has completed.
+pub struct Tweens<C>(pub Vec<Tween<C>>);
+impl<C: Component> Component for Tweens<C> {
+    type Storage = TableStorage;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        let entity = World::new().spawn().id();
+        let mut layers = Layers::default();
+        let mut width = Tween::new(
+            FieldLens::new(
+                |style: &Style| style.size.width,
+                |style: &mut Style, width| style.size.width = width,
+                Val::Px(100.0),
+            ),
+            Vars::default(),
+        );
+        let mut left = Tween::new(
+            FieldLens::new(
+                |style: &Style| style.margin.left,
+                |style: &mut Style, left| style.margin.left = left,
+                Val::Px(20.0),
+            ),
+            Vars::default(),
+        );
+        let mut style = Style {
+            size: Size::new(Val::Px(0.0), Val::Px(50.0)),
+            margin: Rect::all(Val::Px(0.0)),
+            ..Default::default()
+        };
+        width.capture(&style);
+        left.capture(&style);
+        width.apply(&mut style, &mut layers, entity, 0.5);
+        left.apply(&mut style, &mut layers, entity, 0.5);
+        assert_eq!(style.size, Size::new(Val::Px(50.0), Val::Px(50.0)));
+        assert_eq!(style.margin.left, Val::Px(10.0));
+        width.apply(&mut style, &mut layers, entity, 1.0);
+        assert_eq!(style.size.width, Val::Px(100.0));
+        assert_eq!(style.margin.left, Val::Px(10.0));
+        left.apply(&mut style, &mut layers, entity, 1.0);
+        assert_eq!(style.size.width, Val::Px(100.0));
+        assert_eq!(style.margin.left, Val::Px(20.0));
+        assert_eq!(style.margin.right, Val::Px(0.0));
+    }
+
+    #[test]
+    fn additive() {
+        let entity = World::new().spawn().id();
+        let mut layers = Layers::default();
+        let tween = |target, blend| {
+            let lens = FieldLens::new(|v: &Vec2| v.x, |v: &mut Vec2, x| v.x = x, target);
+            Tween::new(
+                lens.named("x"),
+                Vars {
+                    blend,
+                    ..Default::default()
+                },
+            )
+        };
+        let mut value = Vec2::new(1.0, 0.0);
+        let mut breathing = tween(1.5, Blend::Additive);
+        let mut pop = tween(3.0, Blend::Additive);
+        breathing.capture(&value);
+        breathing.apply(&mut value, &mut layers, entity, 0.5);
+        assert_eq!(value.x, 1.25);
+        // Starting partway through the breathing, the pop still measures its offset from the base value.
+        pop.capture(&value);
+        pop.apply(&mut value, &mut layers, entity, 0.5);
+        assert_eq!(value.x, 2.25);
+        pop.apply(&mut value, &mut layers, entity, 1.0);
+        assert_eq!(value.x, 3.25);
+        breathing.apply(&mut value, &mut layers, entity, 0.0);
+        assert_eq!(value.x, 3.0);
+
+        // A replacing writer sets the base value, and the offsets stay on top of it.
+        let mut replace = tween(0.0, Blend::Replace);
+        replace.capture(&value);
+        replace.apply(&mut value, &mut layers, entity, 0.5);
+        assert_eq!(value.x, 1.5);
+        breathing.apply(&mut value, &mut layers, entity, 1.0);
+        assert_eq!(value.x, 4.0);
+        pop.apply(&mut value, &mut layers, entity, 0.0);
+        assert_eq!(value.x, 2.0);
+    }
+}
@@ -1,13 +1,73 @@
 // Reference: https://github.com/vleue/bevy_easings
 use bevy::prelude::*;
 
+/// Linear interpolation between two values of the same type.
+///
+/// Implement it by hand or with `#[derive(Lerp)]` to animate custom types.
 pub trait Lerp {
+    /// Interpolates from `self` to `target`, where a `delta` of `0.0` yields `self` and `1.0` yields `target`.
     fn lerp(&self, target: &Self, delta: f32) -> Self;
+    /// Adds the change from `from` to `to` onto `self`, to blend several animations additively.
+    ///
+    /// The default implementation computes `self + to - from` by extrapolating with [`Lerp::lerp`]. It's only
+    /// valid for types whose `lerp` is linear and extrapolates past `1.0`: types clamping the delta, switching
+    /// between values or returning `self` for values they can't interpolate, like [`Val`]s of different
+    /// variants, must implement it themselves.
+    fn accumulate(&self, from: &Self, to: &Self) -> Self
+    where
+        Self: Sized,
+    {
+        // `2 * self - from`
+        let reflected = from.lerp(self, 2.0);
+        // `self + (to - from) / 2`
+        let midpoint = to.lerp(&reflected, 0.5);
+        self.lerp(&midpoint, 2.0)
+    }
 }
 impl Lerp for f32 {
     fn lerp(&self, target: &f32, delta: f32) -> f32 {
         self + (target - self) * delta
     }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        self + to - from
+    }
+}
+impl Lerp for Vec2 {
+    fn lerp(&self, target: &Self, delta: f32) -> Self {
+        Vec2::lerp(*self, *target, delta)
+    }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        *self + *to - *from
+    }
+}
+impl Lerp for Vec3 {
+    fn lerp(&self, target: &Self, delta: f32) -> Self {
+        Vec3::lerp(*self, *target, delta)
+    }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        *self + *to - *from
+    }
+}
+impl Lerp for Vec4 {
+    fn lerp(&self, target: &Self, delta: f32) -> Self {
+        Vec4::lerp(*self, *target, delta)
+    }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        *self + *to - *from
+    }
+}
+impl Lerp for Quat {
+    fn lerp(&self, target: &Self, delta: f32) -> Self {
+        Quat::lerp(*self, *target, delta)
+    }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        (*to * from.inverse() * *self).normalize()
+    }
+}
+impl Lerp for Color {
+    fn lerp(&self, target: &Self, delta: f32) -> Self {
+        Vec4::from(*self).lerp(Vec4::from(*target), delta).into()
+    }
 }
 impl Lerp for Val {
     fn lerp(&self, target: &Self, delta: f32) -> Self {
@@ -19,6 +79,17 @@ impl Lerp for Val {
             _ => *self,
         }
     }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        match (*self, *from, *to) {
+            (Val::Px(value), Val::Px(from), Val::Px(to)) => Val::Px(value + to - from),
+            (Val::Percent(value), Val::Percent(from), Val::Percent(to)) => {
+                Val::Percent(value + to - from)
+            }
+            _ if from == to => *self,
+            // A change in another unit can't be added without the layout, so it's written as is.
+            _ => *to,
+        }
+    }
 }
 impl Lerp for Size<Val> {
     fn lerp(&self, target: &Self, delta: f32) -> Self {
@@ -27,6 +98,12 @@ impl Lerp for Size<Val> {
             height: self.height.lerp(&target.height, delta),
         }
     }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        Size {
+            width: self.width.accumulate(&from.width, &to.width),
+            height: self.height.accumulate(&from.height, &to.height),
+        }
+    }
 }
 impl Lerp for Rect<Val> {
     fn lerp(&self, target: &Self, delta: f32) -> Self {
@@ -37,6 +114,14 @@ impl Lerp for Rect<Val> {
             bottom: self.bottom.lerp(&target.bottom, delta),
         }
     }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        Rect {
+            left: self.left.accumulate(&from.left, &to.left),
+            right: self.right.accumulate(&from.right, &to.right),
+            top: self.top.accumulate(&from.top, &to.top),
+            bottom: self.bottom.accumulate(&from.bottom, &to.bottom),
+        }
+    }
 }
 impl Lerp for Style {
     fn lerp(&self, target: &Self, delta: f32) -> Self {
@@ -45,14 +130,45 @@ impl Lerp for Style {
             margin: self.margin.lerp(&target.margin, delta),
             padding: self.padding.lerp(&target.padding, delta),
             border: self.border.lerp(&target.border, delta),
+            flex_grow: self.flex_grow.lerp(&target.flex_grow, delta),
+            flex_shrink: self.flex_shrink.lerp(&target.flex_shrink, delta),
+            flex_basis: self.flex_basis.lerp(&target.flex_basis, delta),
             size: self.size.lerp(&target.size, delta),
+            min_size: self.min_size.lerp(&target.min_size, delta),
+            max_size: self.max_size.lerp(&target.max_size, delta),
+            aspect_ratio: match (self.aspect_ratio, target.aspect_ratio) {
+                (Some(source), Some(target)) => Some(source.lerp(&target, delta)),
+                _ => self.aspect_ratio,
+            },
+            ..*self
+        }
+    }
+    /// Adds the changes of the interpolated fields, the others keeping the value of `self`.
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        Style {
+            position: self.position.accumulate(&from.position, &to.position),
+            margin: self.margin.accumulate(&from.margin, &to.margin),
+            padding: self.padding.accumulate(&from.padding, &to.padding),
+            border: self.border.accumulate(&from.border, &to.border),
+            flex_grow: self.flex_grow.accumulate(&from.flex_grow, &to.flex_grow),
+            flex_shrink: self
+                .flex_shrink
+                .accumulate(&from.flex_shrink, &to.flex_shrink),
+            flex_basis: self.flex_basis.accumulate(&from.flex_basis, &to.flex_basis),
+            size: self.size.accumulate(&from.size, &to.size),
+            min_size: self.min_size.accumulate(&from.min_size, &to.min_size),
+            max_size: self.max_size.accumulate(&from.max_size, &to.max_size),
+            aspect_ratio: match (self.aspect_ratio, from.aspect_ratio, to.aspect_ratio) {
+                (Some(value), Some(from), Some(to)) => Some(value.accumulate(&from, &to)),
+                _ => self.aspect_ratio,
+            },
             ..*self
         }
     }
 }
 impl Lerp for UiColor {
     fn lerp(&self, target: &Self, delta: f32) -> Self {
-        UiColor(Vec4::from(self.0).lerp(Vec4::from(target.0), delta).into())
+        UiColor(self.0.lerp(&target.0, delta))
     }
 }
 impl Lerp for Transform {
@@ -63,11 +179,66 @@ impl Lerp for Transform {
             scale: self.scale.lerp(target.scale, delta),
         }
     }
+    fn accumulate(&self, from: &Self, to: &Self) -> Self {
+        Transform {
+            translation: self
+                .translation
+                .accumulate(&from.translation, &to.translation),
+            rotation: self.rotation.accumulate(&from.rotation, &to.rotation),
+            scale: self.scale.accumulate(&from.scale, &to.scale),
+        }
+    }
+}
+
+/// How the rotation of a [`Transform`] is interpolated.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RotationInterpolation {
+    /// Normalized linear interpolation along the shortest path, cheap but faster in the middle.
+    #[default]
+    Nlerp,
+    /// Spherical interpolation at constant angular velocity, following the quaternions as given.
+    Slerp,
+    /// Spherical interpolation along the shortest path.
+    ShortestPath,
+    /// Spherical interpolation along the longest path, going the other way around.
+    LongestPath,
+}
+impl RotationInterpolation {
+    /// Interpolates from the `source` to the `target` rotation.
+    pub(crate) fn interpolate(self, source: Quat, target: Quat, delta: f32) -> Quat {
+        let dot = source.dot(target);
+        match self {
+            Self::Nlerp => source.lerp(target, delta),
+            Self::Slerp => slerp(source, target, delta),
+            Self::ShortestPath if dot < 0.0 => slerp(source, -target, delta),
+            Self::LongestPath if dot > 0.0 => slerp(source, -target, delta),
+            Self::ShortestPath | Self::LongestPath => slerp(source, target, delta),
+        }
+    }
+}
+
+/// Spherical interpolation without picking the shortest path.
+fn slerp(source: Quat, target: Quat, delta: f32) -> Quat {
+    let dot = source.dot(target).clamp(-1.0, 1.0);
+    let theta = dot.acos();
+    let sin = theta.sin();
+    if sin.abs() < 1e-4 {
+        // Either the same rotation, or a full turn around an undefined axis.
+        return if dot > 0.0 || delta >= 1.0 {
+            target
+        } else {
+            source
+        };
+    }
+    let a = ((1.0 - delta) * theta).sin() / sin;
+    let b = (delta * theta).sin() / sin;
+    (source * a + target * b).normalize()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Lerp;
 
     #[test]
     fn style() {
@@ -76,7 +247,13 @@ mod tests {
             margin: Rect::all(Val::Px(0.0)),
             padding: Rect::all(Val::Px(0.0)),
             border: Rect::all(Val::Px(0.0)),
+            flex_grow: 0.0,
+            flex_shrink: 0.0,
+            flex_basis: Val::Px(0.0),
             size: Size::new(Val::Px(0.0), Val::Px(0.0)),
+            min_size: Size::new(Val::Px(0.0), Val::Px(0.0)),
+            max_size: Size::new(Val::Px(0.0), Val::Px(0.0)),
+            aspect_ratio: Some(1.0),
             ..Default::default()
         };
         let target = &Style {
@@ -84,7 +261,13 @@ mod tests {
             margin: Rect::all(Val::Px(10.0)),
             padding: Rect::all(Val::Px(10.0)),
             border: Rect::all(Val::Px(10.0)),
+            flex_grow: 2.0,
+            flex_shrink: 2.0,
+            flex_basis: Val::Px(10.0),
             size: Size::new(Val::Px(50.0), Val::Px(50.0)),
+            min_size: Size::new(Val::Px(20.0), Val::Px(20.0)),
+            max_size: Size::new(Val::Px(100.0), Val::Px(100.0)),
+            aspect_ratio: Some(2.0),
             ..Default::default()
         };
 
@@ -93,21 +276,39 @@ mod tests {
         assert_eq!(a.margin, Rect::all(Val::Px(0.0)));
         assert_eq!(a.padding, Rect::all(Val::Px(0.0)));
         assert_eq!(a.border, Rect::all(Val::Px(0.0)));
+        assert_eq!(a.flex_grow, 0.0);
+        assert_eq!(a.flex_shrink, 0.0);
+        assert_eq!(a.flex_basis, Val::Px(0.0));
         assert_eq!(a.size, Size::new(Val::Px(0.0), Val::Px(0.0)));
+        assert_eq!(a.min_size, Size::new(Val::Px(0.0), Val::Px(0.0)));
+        assert_eq!(a.max_size, Size::new(Val::Px(0.0), Val::Px(0.0)));
+        assert_eq!(a.aspect_ratio, Some(1.0));
 
         let b = source.lerp(target, 0.5);
         assert_eq!(b.position, Rect::all(Val::Px(5.0)));
         assert_eq!(b.margin, Rect::all(Val::Px(5.0)));
         assert_eq!(b.padding, Rect::all(Val::Px(5.0)));
         assert_eq!(b.border, Rect::all(Val::Px(5.0)));
+        assert_eq!(b.flex_grow, 1.0);
+        assert_eq!(b.flex_shrink, 1.0);
+        assert_eq!(b.flex_basis, Val::Px(5.0));
         assert_eq!(b.size, Size::new(Val::Px(25.0), Val::Px(25.0)));
+        assert_eq!(b.min_size, Size::new(Val::Px(10.0), Val::Px(10.0)));
+        assert_eq!(b.max_size, Size::new(Val::Px(50.0), Val::Px(50.0)));
+        assert_eq!(b.aspect_ratio, Some(1.5));
 
         let c = source.lerp(target, 1.0);
         assert_eq!(c.position, Rect::all(Val::Px(10.0)));
         assert_eq!(c.margin, Rect::all(Val::Px(10.0)));
         assert_eq!(c.padding, Rect::all(Val::Px(10.0)));
         assert_eq!(c.border, Rect::all(Val::Px(10.0)));
+        assert_eq!(c.flex_grow, 2.0);
+        assert_eq!(c.flex_shrink, 2.0);
+        assert_eq!(c.flex_basis, Val::Px(10.0));
         assert_eq!(c.size, Size::new(Val::Px(50.0), Val::Px(50.0)));
+        assert_eq!(c.min_size, Size::new(Val::Px(20.0), Val::Px(20.0)));
+        assert_eq!(c.max_size, Size::new(Val::Px(100.0), Val::Px(100.0)));
+        assert_eq!(c.aspect_ratio, Some(2.0));
     }
 
     #[test]
@@ -156,5 +357,138 @@ mod tests {
             .rotation
             .abs_diff_eq(Quat::from_rotation_z(100_f32.to_radians()), 1e-5));
         assert!(c.scale.abs_diff_eq(Vec3::new(2.0, 3.0, 4.0), 1e-5));
+
+        let degrees = |degrees: f32| Quat::from_rotation_z(degrees.to_radians());
+        let rotation = |interpolation: RotationInterpolation, target: Quat, delta: f32| {
+            interpolation.interpolate(source.rotation, target, delta)
+        };
+        let nlerp = rotation(RotationInterpolation::Nlerp, target.rotation, 0.25);
+        assert!(nlerp.angle_between(degrees(25.0)) > 1e-2);
+        for delta in [0.0, 0.25, 0.5, 1.0] {
+            let slerp = rotation(RotationInterpolation::Slerp, target.rotation, delta);
+            assert!(slerp.angle_between(degrees(100.0 * delta)) < 1e-3);
+            let shortest = rotation(RotationInterpolation::ShortestPath, -target.rotation, delta);
+            assert!(shortest.angle_between(degrees(100.0 * delta)) < 1e-3);
+            let longest = rotation(RotationInterpolation::LongestPath, target.rotation, delta);
+            assert!(longest.angle_between(degrees(-260.0 * delta)) < 1e-3);
+        }
+        let slerp = rotation(RotationInterpolation::Slerp, -target.rotation, 0.5);
+        assert!(slerp.angle_between(degrees(-130.0)) < 1e-3);
+        let longest = rotation(RotationInterpolation::LongestPath, source.rotation, 0.5);
+        assert!(longest.abs_diff_eq(source.rotation, 1e-5));
+    }
+
+    #[test]
+    fn accumulate() {
+        assert_eq!(1_f32.accumulate(&2.0, &5.0), 4.0);
+        assert_eq!(
+            Val::Px(1.0).accumulate(&Val::Px(2.0), &Val::Px(5.0)),
+            Val::Px(4.0)
+        );
+        assert_eq!(
+            Vec3::ONE.accumulate(&Vec3::ONE, &Vec3::splat(1.5)),
+            Vec3::splat(1.5)
+        );
+        assert_eq!(
+            Val::Percent(10.0).accumulate(&Val::Percent(20.0), &Val::Percent(25.0)),
+            Val::Percent(15.0)
+        );
+        // Changes in another unit than the base value are written as is.
+        assert_eq!(
+            Val::Px(10.0).accumulate(&Val::Percent(20.0), &Val::Percent(25.0)),
+            Val::Percent(25.0)
+        );
+        assert_eq!(
+            Val::Px(10.0).accumulate(&Val::Px(20.0), &Val::Percent(25.0)),
+            Val::Percent(25.0)
+        );
+        assert_eq!(
+            Val::Px(10.0).accumulate(&Val::Auto, &Val::Auto),
+            Val::Px(10.0)
+        );
+        let margin = Rect {
+            left: Val::Px(10.0),
+            top: Val::Percent(5.0),
+            ..Default::default()
+        };
+        let from = Rect::all(Val::Px(0.0));
+        let to = Rect {
+            left: Val::Px(4.0),
+            top: Val::Px(3.0),
+            ..from
+        };
+        let value = margin.accumulate(&from, &to);
+        assert_eq!(value.left, Val::Px(14.0));
+        assert_eq!(value.top, Val::Px(3.0));
+        assert_eq!(value.right, Val::Undefined);
+
+        let breathing = Transform::from_scale(Vec3::splat(1.1));
+        let pop = Transform::from_scale(Vec3::splat(1.5));
+        let base = Transform::default();
+        let value = base
+            .accumulate(&base, &breathing)
+            .accumulate(&base, &pop)
+            .accumulate(&base, &Transform::from_rotation(Quat::from_rotation_z(1.0)));
+        assert!(value.scale.abs_diff_eq(Vec3::splat(1.6), 1e-5));
+        assert!(value.rotation.abs_diff_eq(Quat::from_rotation_z(1.0), 1e-5));
+        let value = value.accumulate(&pop, &base);
+        assert!(value.scale.abs_diff_eq(Vec3::splat(1.1), 1e-5));
+    }
+
+    #[test]
+    fn derive() {
+        fn midpoint(source: &f32, target: &f32, _: f32) -> f32 {
+            (source + target) / 2.0
+        }
+
+        #[derive(Lerp, Debug, PartialEq)]
+        struct Alpha(f32);
+
+        #[derive(Lerp, Debug, PartialEq)]
+        struct Panel {
+            size: Vec2,
+            alpha: Alpha,
+            #[lerp(skip)]
+            label: String,
+            #[lerp(snap = 0.5)]
+            open: bool,
+            #[lerp(with = "midpoint")]
+            offset: f32,
+        }
+
+        let source = Panel {
+            size: Vec2::ZERO,
+            alpha: Alpha(0.0),
+            label: "source".to_string(),
+            open: false,
+            offset: 0.0,
+        };
+        let target = &Panel {
+            size: Vec2::new(100.0, 50.0),
+            alpha: Alpha(1.0),
+            label: "target".to_string(),
+            open: true,
+            offset: 10.0,
+        };
+
+        let a = source.lerp(target, 0.25);
+        assert_eq!(a.size, Vec2::new(25.0, 12.5));
+        assert_eq!(a.alpha, Alpha(0.25));
+        assert_eq!(a.label, "source");
+        assert!(!a.open);
+        assert_eq!(a.offset, 5.0);
+
+        let b = source.lerp(target, 0.5);
+        assert_eq!(b.size, Vec2::new(50.0, 25.0));
+        assert_eq!(b.alpha, Alpha(0.5));
+        assert_eq!(b.label, "source");
+        assert!(b.open);
+        // Only the interpolated fields add their change.
+        let c = b.accumulate(&source, target);
+        assert_eq!(c.size, Vec2::new(150.0, 75.0));
+        assert_eq!(c.alpha, Alpha(1.5));
+        assert_eq!(c.label, "source");
+        assert!(c.open);
+        assert_eq!(c.offset, 5.0);
     }
 }
@@ -1,19 +1,74 @@
-use bevy::prelude::*;
+extern crate self as bevy_ui_animation;
+
+use bevy::{math::EulerRot, prelude::*};
+pub use bevy_ui_animation_derive::Lerp;
+pub use blend::{Blend, Layer, Layers};
+pub use color::{ColorSpace, Gradient, HuePath};
 pub use ease::Ease;
-pub use event::CompleteEvent;
-pub use plugin::AnimationPlugin;
+pub use event::{CompleteEvent, TextRevealEvent};
+pub use frames::{FrameTiming, Frames};
+pub use group::GroupOpacity;
+pub use lens::{FieldLens, Lens, Tween, Tweens};
+pub use lerp::{Lerp, RotationInterpolation};
+pub use opacity::{AutoAlpha, Opacity};
+pub use origin::TransformOrigin;
+pub use plugin::{tween_system, AnimationPlugin};
+pub use shake::Shake;
+pub use sprite::{AtlasTarget, SpriteTarget};
+pub use style::{Discrete, StyleTarget};
+pub use text::{
+    merge_text, split_text, Cursor, NumberFormat, ScrambleOrder, SplitText, TextColor, TextCounter,
+    TextFontSize, TextReveal, TextScramble, TextSections, TextUnit,
+};
+pub use transform::TransformTarget;
+
+use playhead::Playhead;
+use plugin::Source;
 
+mod blend;
+mod color;
 mod ease;
 mod event;
+mod frames;
+mod group;
+mod lens;
 mod lerp;
+mod opacity;
+mod origin;
+mod perspective;
+mod playhead;
 mod plugin;
+mod random;
+mod shake;
+mod sprite;
+mod style;
+mod text;
+mod transform;
 
 pub struct Vars {
-    pub style: Option<Style>,
+    pub style: Option<StyleTarget>,
+    pub sprite: Option<SpriteTarget>,
+    pub atlas: Option<AtlasTarget>,
     pub color: Option<UiColor>,
-    pub transform: Option<Transform>,
+    pub transform: Option<TransformTarget>,
     pub transform_rotation: Option<TransformRotation>,
+    pub euler_rotation: Option<EulerRotation>,
+    pub transform_origin: Option<TransformOrigin>,
+    pub perspective: Option<f32>,
+    pub shake: Option<Shake>,
     pub text_color: Option<TextColor>,
+    pub text_font_size: Option<TextFontSize>,
+    pub text_counter: Option<TextCounter>,
+    pub text_reveal: Option<TextReveal>,
+    pub text_scramble: Option<TextScramble>,
+    pub gradient: Option<Gradient>,
+    pub frames: Option<Frames>,
+    pub opacity: Option<Opacity>,
+    pub group_opacity: Option<f32>,
+    pub color_space: ColorSpace,
+    pub rotation_interpolation: RotationInterpolation,
+    pub blend: Blend,
+    pub stagger: f32,
     pub delay: f32,
     pub duration: f32,
     pub ease: Ease,
@@ -25,10 +80,28 @@ impl Default for Vars {
     fn default() -> Self {
         Self {
             style: None,
+            sprite: None,
+            atlas: None,
             color: None,
             transform: None,
             transform_rotation: None,
+            euler_rotation: None,
+            transform_origin: None,
+            perspective: None,
+            shake: None,
             text_color: None,
+            text_font_size: None,
+            text_counter: None,
+            text_reveal: None,
+            text_scramble: None,
+            gradient: None,
+            frames: None,
+            opacity: None,
+            group_opacity: None,
+            color_space: ColorSpace::default(),
+            rotation_interpolation: RotationInterpolation::default(),
+            blend: Blend::default(),
+            stagger: 0.0,
             delay: 0.0,
             duration: 0.5,
             ease: ease::Ease::ExpoOut,
@@ -68,29 +141,46 @@ impl TransformRotation {
         }
     }
 }
-/// Manipulates the color field of a section of a [`Text`] component.
-pub struct TextColor {
-    /// Target color.
-    pub target: Color,
-    /// Index of the text section in the [`Text`] component.
-    pub section: usize,
+/// Rotates a [`Transform`] component by Euler angles on top of its starting rotation.
+///
+/// The angles are interpolated before building the rotation, so turns accumulate and a 720 degree spin
+/// goes around twice. Like [`TransformRotation`], positive degrees rotate clockwise.
+pub struct EulerRotation {
+    /// Order in which the rotations around the axes are applied.
+    pub order: EulerRot,
+    /// Degrees to rotate around each axis.
+    pub degrees: Vec3,
+}
+impl EulerRotation {
+    /// Rotates by `degrees` around each axis, applied in `order`.
+    pub fn new(order: EulerRot, degrees: Vec3) -> Self {
+        Self { order, degrees }
+    }
+    /// Rotates by `x`, `y` and `z` degrees, applied in XYZ order.
+    pub fn xyz(x: f32, y: f32, z: f32) -> Self {
+        Self::new(EulerRot::XYZ, Vec3::new(x, y, z))
+    }
+    /// The rotation added to the starting rotation at `delta`.
+    pub(crate) fn rotation(&self, delta: f32) -> Quat {
+        let [a, b, c] = (self.degrees * -delta).to_array();
+        Quat::from_euler(self.order, a.to_radians(), b.to_radians(), c.to_radians())
+    }
 }
-
 #[derive(Component)]
 pub struct Animation {
-    timer: Timer,
-    delay_timer: Timer,
-    direction: i16,
+    playhead: Playhead,
     vars: Vars,
+    source: Option<Source>,
+    id: u64,
 }
 impl Animation {
     /// Create a new animation.
     pub fn new(vars: Vars) -> Self {
         Self {
-            timer: Timer::from_seconds(vars.duration, false),
-            delay_timer: Timer::from_seconds(vars.delay, false),
-            direction: 1,
+            playhead: Playhead::new(&vars),
             vars,
+            source: None,
+            id: blend::next_id(),
         }
     }
     /// Gets the animation's paused state which indicates whether or not the animation is currently paused.
@@ -106,3 +196,33 @@ impl Animation {
         self.vars.paused = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    #[test]
+    fn euler_rotation() {
+        let rotation = EulerRotation::xyz(0.0, 0.0, 720.0);
+        assert!(rotation.rotation(0.0).angle_between(Quat::IDENTITY) < 1e-3);
+        assert!(
+            rotation
+                .rotation(0.25)
+                .angle_between(Quat::from_rotation_z(PI))
+                < 1e-3
+        );
+        assert!(rotation.rotation(0.5).angle_between(Quat::IDENTITY) < 1e-3);
+        assert!(
+            rotation
+                .rotation(0.625)
+                .angle_between(Quat::from_rotation_z(-FRAC_PI_2))
+                < 1e-3
+        );
+
+        let rotation = EulerRotation::new(EulerRot::YXZ, Vec3::new(-90.0, 0.0, 0.0));
+        let direction = rotation.rotation(1.0) * Vec3::X;
+        assert!(direction.abs_diff_eq(Vec3::new(0.0, 0.0, -1.0), 1e-5));
+    }
+}
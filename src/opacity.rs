@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+
+use crate::lerp::Lerp;
+
+/// Animates the alpha of the [`UiColor`] and of every [`Text`] section, keeping their RGB.
+pub struct Opacity {
+    /// Target alpha.
+    pub target: f32,
+    /// How to hide the entity while it's fully transparent, like GSAP's autoAlpha.
+    pub auto_alpha: Option<AutoAlpha>,
+}
+impl Opacity {
+    /// Fades to the `target` alpha.
+    pub fn new(target: f32) -> Self {
+        Self {
+            target,
+            auto_alpha: None,
+        }
+    }
+    /// Fades to the `target` alpha, hiding the entity with `auto_alpha` while it's fully transparent.
+    pub fn auto_alpha(target: f32, auto_alpha: AutoAlpha) -> Self {
+        Self {
+            target,
+            auto_alpha: Some(auto_alpha),
+        }
+    }
+    /// Fades the alpha of `color` and `text` from their sources, then hides or shows the entity.
+    pub(crate) fn apply(
+        &self,
+        delta: f32,
+        color: Option<(UiColor, &mut UiColor)>,
+        text: Option<(&Text, &mut Text)>,
+        display: Option<(Display, &mut Style)>,
+        visibility: Option<&mut Visibility>,
+    ) {
+        let alpha = |source: f32| source.lerp(&self.target, delta).clamp(0.0, 1.0);
+        let mut alphas = Vec::new();
+        if let Some((source, color)) = color {
+            color.0.set_a(alpha(source.0.a()));
+            alphas.push(color.0.a());
+        }
+        if let Some((source, text)) = text {
+            for (section, source) in text.sections.iter_mut().zip(&source.sections) {
+                section.style.color.set_a(alpha(source.style.color.a()));
+                alphas.push(section.style.color.a());
+            }
+        }
+        // Without any alpha written, there's nothing to tell whether the entity is transparent.
+        let auto_alpha = self.auto_alpha.filter(|_| !alphas.is_empty());
+        let visible = alphas.into_iter().any(|alpha| alpha > 0.0);
+        match (auto_alpha, display, visibility) {
+            (Some(AutoAlpha::Visibility), _, Some(visibility)) => {
+                visibility.is_visible = visible;
+            }
+            (Some(AutoAlpha::Display), Some((source, style)), _) => {
+                style.display = match (visible, source) {
+                    (false, _) => Display::None,
+                    (true, Display::None) => Display::Flex,
+                    (true, display) => display,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Hides an entity once its alpha reaches `0.0` and shows it again as soon as it rises above.
+///
+/// Entities without a [`UiColor`] or [`Text`] to fade keep their visibility.
+#[derive(Clone, Copy)]
+pub enum AutoAlpha {
+    /// Toggles [`Visibility::is_visible`].
+    Visibility,
+    /// Toggles [`Style::display`] between [`Display::None`] and its original value.
+    ///
+    /// A node starting hidden with [`Display::None`] is shown with [`Display::Flex`].
+    Display,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alpha() {
+        let source = UiColor(Color::rgba(1.0, 0.5, 0.0, 1.0));
+        let mut color = source;
+        let mut text = Text {
+            sections: vec![TextSection::default(); 2],
+            ..Default::default()
+        };
+        text.sections[1].style.color = Color::rgba(0.0, 0.0, 1.0, 0.5);
+        let text_source = text.clone();
+        let opacity = Opacity::new(0.0);
+        opacity.apply(
+            0.5,
+            Some((source, &mut color)),
+            Some((&text_source, &mut text)),
+            None,
+            None,
+        );
+        assert_eq!(color.0, Color::rgba(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(
+            text.sections[0].style.color,
+            Color::rgba(1.0, 1.0, 1.0, 0.5)
+        );
+        assert_eq!(
+            text.sections[1].style.color,
+            Color::rgba(0.0, 0.0, 1.0, 0.25)
+        );
+    }
+
+    #[test]
+    fn visibility() {
+        let source = UiColor(Color::WHITE);
+        let mut color = source;
+        let mut visibility = Visibility::default();
+        let opacity = Opacity::auto_alpha(0.0, AutoAlpha::Visibility);
+        opacity.apply(
+            1.0,
+            Some((source, &mut color)),
+            None,
+            None,
+            Some(&mut visibility),
+        );
+        assert!(!visibility.is_visible);
+        opacity.apply(
+            0.9,
+            Some((source, &mut color)),
+            None,
+            None,
+            Some(&mut visibility),
+        );
+        assert!(visibility.is_visible);
+
+        // Without a color or text to fade, the visibility is left alone.
+        visibility.is_visible = false;
+        opacity.apply(0.5, None, None, None, Some(&mut visibility));
+        assert!(!visibility.is_visible);
+    }
+
+    #[test]
+    fn display() {
+        let source = UiColor(Color::WHITE);
+        let mut color = source;
+        let mut style = Style::default();
+        let opacity = Opacity::auto_alpha(0.0, AutoAlpha::Display);
+        opacity.apply(
+            1.0,
+            Some((source, &mut color)),
+            None,
+            Some((Display::Flex, &mut style)),
+            None,
+        );
+        assert_eq!(style.display, Display::None);
+        opacity.apply(
+            0.5,
+            Some((source, &mut color)),
+            None,
+            Some((Display::Flex, &mut style)),
+            None,
+        );
+        assert_eq!(style.display, Display::Flex);
+
+        let source = UiColor(Color::NONE);
+        let opacity = Opacity::auto_alpha(1.0, AutoAlpha::Display);
+        style.display = Display::None;
+        opacity.apply(
+            0.5,
+            Some((source, &mut color)),
+            None,
+            Some((Display::None, &mut style)),
+            None,
+        );
+        assert_eq!(style.display, Display::Flex);
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+/// The point a [`Transform`] rotates and scales around, instead of the center of the node or sprite.
+#[derive(Clone, Copy, Debug)]
+pub enum TransformOrigin {
+    /// Normalized coordinates from the top-left corner, `(0.5, 0.5)` being the center.
+    Normalized(Vec2),
+    /// Pixels from the top-left corner.
+    Px(Vec2),
+}
+impl TransformOrigin {
+    /// The offset of the origin from the center of an area of `size`, with Y up.
+    fn offset(&self, size: Vec2) -> Vec3 {
+        let from_top_left = match *self {
+            Self::Normalized(origin) => origin * size,
+            Self::Px(origin) => origin,
+        };
+        Vec3::new(
+            from_top_left.x - size.x / 2.0,
+            size.y / 2.0 - from_top_left.y,
+            0.0,
+        )
+    }
+    /// The translation keeping the origin in place while rotating and scaling around the center.
+    pub(crate) fn compensation(&self, size: Vec2, rotation: Quat, scale: Vec3) -> Vec2 {
+        let offset = self.offset(size);
+        (offset - rotation * (scale * offset)).truncate()
+    }
+}
+
+/// Offsets `position` by `offset` pixels with Y up, converted to a percentage of `parent` for percentages.
+///
+/// Each axis is offset through the side it's anchored to, `left` and `top` for an axis anchored by neither.
+pub(crate) fn offset_position(position: Rect<Val>, offset: Vec2, parent: Vec2) -> Rect<Val> {
+    let anchored = |val: Val| !matches!(val, Val::Undefined | Val::Auto);
+    let mut position = position;
+    if !anchored(position.left) && anchored(position.right) {
+        position.right = offset_val(position.right, -offset.x, parent.x);
+    } else {
+        position.left = offset_val(position.left, offset.x, parent.x);
+    }
+    if !anchored(position.top) && anchored(position.bottom) {
+        position.bottom = offset_val(position.bottom, offset.y, parent.y);
+    } else {
+        position.top = offset_val(position.top, -offset.y, parent.y);
+    }
+    position
+}
+
+/// Offsets a position by `offset` pixels, converted to a percentage of `parent` pixels for percentages.
+fn offset_val(val: Val, offset: f32, parent: f32) -> Val {
+    match val {
+        _ if offset == 0.0 => val,
+        Val::Px(value) => Val::Px(value + offset),
+        Val::Undefined | Val::Auto => Val::Px(offset),
+        Val::Percent(value) if parent > 0.0 => Val::Percent(value + offset / parent * 100.0),
+        Val::Percent(_) => val,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn compensation() {
+        let size = Vec2::new(100.0, 50.0);
+        let center = TransformOrigin::Normalized(Vec2::splat(0.5));
+        let c = center.compensation(size, Quat::from_rotation_z(1.0), Vec3::splat(2.0));
+        assert!(c.abs_diff_eq(Vec2::ZERO, 1e-5));
+
+        let top_left = TransformOrigin::Normalized(Vec2::ZERO);
+        let c = top_left.compensation(size, Quat::IDENTITY, Vec3::splat(2.0));
+        assert!(c.abs_diff_eq(Vec2::new(50.0, -25.0), 1e-5));
+
+        let left = TransformOrigin::Px(Vec2::new(0.0, 25.0));
+        let c = left.compensation(size, Quat::from_rotation_z(FRAC_PI_2), Vec3::ONE);
+        assert!(c.abs_diff_eq(Vec2::new(-50.0, 50.0), 1e-4));
+    }
+
+    #[test]
+    fn offset() {
+        assert_eq!(offset_val(Val::Px(10.0), 5.0, 200.0), Val::Px(15.0));
+        assert_eq!(offset_val(Val::Undefined, -5.0, 200.0), Val::Px(-5.0));
+        assert_eq!(offset_val(Val::Undefined, 0.0, 200.0), Val::Undefined);
+        assert_eq!(
+            offset_val(Val::Percent(10.0), 5.0, 200.0),
+            Val::Percent(12.5)
+        );
+        assert_eq!(offset_val(Val::Percent(10.0), 5.0, 0.0), Val::Percent(10.0));
+    }
+
+    #[test]
+    fn anchored() {
+        let parent = Vec2::new(200.0, 100.0);
+        let position = Rect {
+            right: Val::Px(10.0),
+            bottom: Val::Percent(10.0),
+            ..Default::default()
+        };
+        let offset = offset_position(position, Vec2::new(5.0, 5.0), parent);
+        assert_eq!(offset.left, Val::Undefined);
+        assert_eq!(offset.top, Val::Undefined);
+        assert_eq!(offset.right, Val::Px(5.0));
+        assert_eq!(offset.bottom, Val::Percent(15.0));
+
+        let position = Rect {
+            left: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..Default::default()
+        };
+        let offset = offset_position(position, Vec2::new(5.0, 5.0), parent);
+        assert_eq!(offset.left, Val::Px(15.0));
+        assert_eq!(offset.right, Val::Px(10.0));
+        assert_eq!(offset.top, Val::Px(-5.0));
+        assert_eq!(offset.bottom, Val::Undefined);
+    }
+
+    #[test]
+    fn settle() {
+        // Once the shake and the compensation are back to zero, the position is exactly the source again.
+        let parent = Vec2::new(200.0, 100.0);
+        let anchors = [
+            Rect::default(),
+            Rect {
+                right: Val::Px(10.0),
+                bottom: Val::Px(20.0),
+                ..Default::default()
+            },
+            Rect {
+                left: Val::Percent(10.0),
+                bottom: Val::Percent(20.0),
+                ..Default::default()
+            },
+        ];
+        for position in anchors {
+            assert_eq!(offset_position(position, Vec2::ZERO, parent), position);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+/// The scale simulating a perspective seen from `distance` pixels for an area of `size` turned by `rotation`.
+///
+/// The edges turned towards the viewer grow while the ones turned away shrink, which averages out to a
+/// uniform scale of `d² / (d² - z²)`, `z` being the depth of the edges.
+/// A `distance` of `0.0` or less disables the perspective.
+pub(crate) fn perspective_scale(distance: f32, size: Vec2, rotation: Quat) -> f32 {
+    if distance <= 0.0 {
+        return 1.0;
+    }
+    let depth = Vec2::new(
+        (rotation * Vec3::X).z * size.x / 2.0,
+        (rotation * Vec3::Y).z * size.y / 2.0,
+    );
+    let distance = distance * distance;
+    distance / (distance - depth.length_squared().min(distance * 0.9))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    #[test]
+    fn perspective() {
+        let size = Vec2::new(200.0, 100.0);
+        assert_eq!(perspective_scale(500.0, size, Quat::IDENTITY), 1.0);
+        assert_eq!(
+            perspective_scale(500.0, size, Quat::from_rotation_z(1.0)),
+            1.0
+        );
+        let scale = perspective_scale(500.0, size, Quat::from_rotation_y(FRAC_PI_2));
+        assert!((scale - 25.0 / 24.0).abs() < 1e-5);
+        let scale = perspective_scale(500.0, size, Quat::from_rotation_x(PI / 6.0));
+        assert!((scale - 400.0 / 399.0).abs() < 1e-5);
+        assert!((perspective_scale(50.0, size, Quat::from_rotation_y(1.0)) - 10.0).abs() < 1e-3);
+        assert_eq!(perspective_scale(0.0, size, Quat::IDENTITY), 1.0);
+        assert_eq!(
+            perspective_scale(-5.0, size, Quat::from_rotation_y(1.0)),
+            1.0
+        );
+    }
+}
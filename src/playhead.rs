@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{Frames, Vars};
+
+/// Tracks the delay, the elapsed time and the direction of an animation.
+pub(crate) struct Playhead {
+    timer: Timer,
+    delay_timer: Timer,
+    direction: i16,
+}
+impl Playhead {
+    /// Creates a playhead for `vars`, [`Frames`] setting the duration if any.
+    pub(crate) fn new(vars: &Vars) -> Self {
+        let duration = vars.frames.as_ref().map_or(vars.duration, Frames::duration);
+        Self {
+            timer: Timer::from_seconds(duration, false),
+            delay_timer: Timer::from_seconds(vars.delay, false),
+            direction: 1,
+        }
+    }
+    /// Advances the playhead and returns `true` once the delay has elapsed.
+    pub(crate) fn tick(&mut self, delta: Duration) -> bool {
+        let started = self.delay(delta);
+        if started {
+            self.advance(delta);
+        }
+        started
+    }
+    /// Advances the delay only and returns `true` once it has elapsed, the animation then needing an [`advance`].
+    ///
+    /// [`advance`]: Self::advance
+    pub(crate) fn delay(&mut self, delta: Duration) -> bool {
+        self.delay_timer.tick(delta);
+        self.delay_timer.finished()
+    }
+    /// Whether the delay hasn't elapsed yet.
+    pub(crate) fn delayed(&self) -> bool {
+        !self.delay_timer.finished()
+    }
+    /// Advances the current iteration.
+    pub(crate) fn advance(&mut self, delta: Duration) {
+        self.timer.tick(delta);
+    }
+    /// Whether the animation has a non-zero duration to interpolate over.
+    pub(crate) fn has_duration(&self) -> bool {
+        self.timer.duration().as_secs_f32() != 0.0
+    }
+    /// The linear progress of the current iteration, reversed while playing backwards.
+    pub(crate) fn progress(&self) -> f32 {
+        if self.forward() {
+            self.percent()
+        } else {
+            self.timer.percent_left()
+        }
+    }
+    /// The linear progress of the current iteration, regardless of the direction.
+    pub(crate) fn percent(&self) -> f32 {
+        self.timer.percent()
+    }
+    /// The seconds elapsed in the current iteration.
+    pub(crate) fn elapsed(&self) -> f32 {
+        self.timer.elapsed_secs()
+    }
+    /// Whether the playhead is moving forward, as opposed to backwards during a yoyo.
+    pub(crate) fn forward(&self) -> bool {
+        self.direction.is_positive()
+    }
+    /// The duration of an iteration in seconds.
+    pub(crate) fn duration(&self) -> f32 {
+        self.timer.duration().as_secs_f32()
+    }
+    /// Changes the duration of the animation, keeping the elapsed time.
+    pub(crate) fn set_duration(&mut self, seconds: f32) {
+        self.timer.set_duration(Duration::from_secs_f32(seconds));
+    }
+    /// Whether the current iteration finished during the last tick.
+    pub(crate) fn just_finished(&self) -> bool {
+        self.timer.just_finished()
+    }
+    /// Starts the next iteration, flipping the direction if `yoyo` is set.
+    pub(crate) fn restart(&mut self, yoyo: bool) {
+        if yoyo {
+            self.direction *= -1;
+        }
+        self.timer.reset();
+    }
+}
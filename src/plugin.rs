@@ -1,98 +1,456 @@
-use std::collections::{hash_map::Entry, HashMap};
+use bevy::{prelude::*, window::Windows};
 
-use bevy::prelude::*;
+use crate::{
+    blend::{layer_system, Layer, Layers},
+    color,
+    ease::Delta,
+    group::group_opacity_system,
+    lerp::Lerp,
+    style::Layout,
+    text::{split_text_system, staggered},
+    transform::{self, Targeted},
+    Animation, Blend, CompleteEvent, GroupOpacity, TextRevealEvent, Tweens,
+};
 
-use crate::{ease::Delta, lerp::Lerp, Animation, CompleteEvent};
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+enum AnimationSystem {
+    Animation,
+    Tween,
+}
 
 pub struct AnimationPlugin;
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<CompleteEvent>()
-            .add_system(animation_system);
+            .add_event::<TextRevealEvent>()
+            .init_resource::<Layers>()
+            .add_system(split_text_system.before(AnimationSystem::Animation))
+            .add_system(animation_system.label(AnimationSystem::Animation))
+            .add_system(group_opacity_system.after(AnimationSystem::Tween))
+            .add_system_set(
+                SystemSet::new()
+                    .label(AnimationSystem::Tween)
+                    .after(AnimationSystem::Animation)
+                    .with_system(tween_system::<Style>)
+                    .with_system(tween_system::<UiColor>)
+                    .with_system(tween_system::<Transform>)
+                    .with_system(tween_system::<Text>),
+            )
+            .add_system_to_stage(CoreStage::PostUpdate, layer_system);
     }
 }
 
-type SourceMap = HashMap<u32, (Style, Option<UiColor>, Transform, Option<Text>)>;
+/// The components of an entity when its animation started.
+pub(crate) struct Source {
+    style: Option<Style>,
+    color: Option<UiColor>,
+    transform: Option<Transform>,
+    text: Option<Text>,
+    sprite: Option<Sprite>,
+    atlas: Option<TextureAtlasSprite>,
+    layout: Layout,
+    group_opacity: f32,
+    /// The duration of the channels, and of each staggered text section.
+    duration: f32,
+    /// The number of text sections staggered by the text channels.
+    staggered: usize,
+}
+/// The components an animation writes to, grouped as Bevy queries take at most 15 elements.
 type Targets<'a> = (
     Entity,
-    &'a mut Style,
-    Option<&'a mut UiColor>,
-    &'a mut Transform,
-    Option<&'a mut Text>,
     &'a mut Animation,
+    (
+        Option<&'a mut Style>,
+        Option<&'a mut UiColor>,
+        Option<&'a mut Transform>,
+        Option<&'a mut Text>,
+    ),
+    (
+        Option<&'a mut Sprite>,
+        Option<&'a mut TextureAtlasSprite>,
+        Option<&'a mut UiImage>,
+        Option<&'a Handle<Image>>,
+    ),
+    (Option<&'a mut Visibility>, Option<&'a mut GroupOpacity>),
+    (Option<&'a Node>, Option<&'a Parent>, Option<&'a Children>),
 );
 
 fn animation_system(
     time: Res<Time>,
     mut commands: Commands,
-    mut source: Local<SourceMap>,
     mut query: Query<Targets>,
+    nodes: Query<&Node>,
+    windows: Option<Res<Windows>>,
+    mut layers: ResMut<Layers>,
+    images: Option<Res<Assets<Image>>>,
     mut complete_event_writer: EventWriter<CompleteEvent>,
+    mut reveal_event_writer: EventWriter<TextRevealEvent>,
 ) {
-    for (entity, mut style, color, mut transform, text, ref mut animation) in query.iter_mut() {
-        if !animation.vars.paused {
-            animation.delay_timer.tick(time.delta());
-            if animation.delay_timer.finished() {
-                animation.timer.tick(time.delta());
-                if animation.timer.duration().as_secs_f32() != 0.0 {
-                    let progress = if animation.direction.is_positive() {
-                        animation.timer.percent()
-                    } else {
-                        animation.timer.percent_left()
-                    };
-                    let delta = progress.delta(animation.vars.ease);
-                    let entry = source.entry(entity.id()).or_insert((
-                        style.clone(),
-                        color.as_ref().map(|color| **color),
-                        *transform,
-                        text.as_ref().map(|text| (**text).clone()),
-                    ));
-                    if let Some(ref target) = animation.vars.style {
-                        *style = entry.0.lerp(target, delta);
+    let window_size = windows
+        .as_ref()
+        .and_then(|windows| windows.get_primary())
+        .map(|window| Vec2::new(window.width(), window.height()))
+        .unwrap_or_default();
+    for (
+        entity,
+        mut animation,
+        (mut style, mut color, mut transform, mut text),
+        (mut sprite, mut atlas, mut image, handle),
+        (mut visibility, group_opacity),
+        (node, parent, children),
+    ) in query.iter_mut()
+    {
+        let animation = &mut *animation;
+        let started = !animation.vars.paused && animation.playhead.delay(time.delta());
+        // A reveal hides its text as soon as it's inserted, instead of once the delay has elapsed.
+        if animation.source.is_none() && (started || animation.vars.text_reveal.is_some()) {
+            let vars = &animation.vars;
+            if let Some(ref text) = text {
+                let selections = [
+                    vars.text_color.as_ref().map(|t| &t.sections),
+                    vars.text_font_size.as_ref().map(|t| &t.sections),
+                    vars.text_counter.as_ref().map(|t| &t.sections),
+                    vars.text_reveal.as_ref().map(|t| &t.sections),
+                    vars.text_scramble.as_ref().map(|t| &t.sections),
+                    vars.gradient.as_ref().map(|t| &t.sections),
+                ];
+                for sections in selections.into_iter().flatten() {
+                    if let Some(index) = sections.out_of_bounds(text.sections.len()) {
+                        warn!(
+                            "Text section {} of {:?} is out of bounds and won't be animated",
+                            index, entity
+                        );
                     }
-                    if let Some(ref target) = animation.vars.color {
-                        if let Some(mut color) = color {
-                            if let Some(source) = entry.1 {
-                                *color = source.lerp(target, delta);
-                            }
-                        }
+                }
+            }
+            let missing = [
+                ("style", "Style", vars.style.is_some() && style.is_none()),
+                ("color", "UiColor", vars.color.is_some() && color.is_none()),
+                (
+                    "transform",
+                    "Transform",
+                    (vars.transform.is_some()
+                        || vars.transform_rotation.is_some()
+                        || vars.euler_rotation.is_some()
+                        || vars.transform_origin.is_some()
+                        || vars.perspective.is_some()
+                        || vars.shake.is_some())
+                        && transform.is_none(),
+                ),
+                (
+                    "text",
+                    "Text",
+                    (vars.text_color.is_some()
+                        || vars.text_font_size.is_some()
+                        || vars.text_counter.is_some()
+                        || vars.text_reveal.is_some()
+                        || vars.text_scramble.is_some())
+                        && text.is_none(),
+                ),
+                (
+                    "sprite",
+                    "Sprite",
+                    vars.sprite.is_some() && sprite.is_none(),
+                ),
+                (
+                    "atlas",
+                    "TextureAtlasSprite",
+                    vars.atlas.is_some() && atlas.is_none(),
+                ),
+                (
+                    "frames",
+                    "UiImage",
+                    vars.frames.is_some() && image.is_none(),
+                ),
+                (
+                    "gradient",
+                    "UiColor, Text, Sprite or TextureAtlasSprite",
+                    vars.gradient.is_some()
+                        && color.is_none()
+                        && text.is_none()
+                        && sprite.is_none()
+                        && atlas.is_none(),
+                ),
+                (
+                    "opacity",
+                    "UiColor or Text",
+                    vars.opacity.is_some() && color.is_none() && text.is_none(),
+                ),
+                (
+                    "group_opacity",
+                    "UiColor, Text or Children",
+                    vars.group_opacity.is_some()
+                        && color.is_none()
+                        && text.is_none()
+                        && children.is_none(),
+                ),
+            ];
+            for (field, component, _) in missing.iter().filter(|missing| missing.2) {
+                warn!(
+                    "The {} of {:?} won't be animated, the entity has no {} component",
+                    field, entity, component
+                );
+            }
+            if let (Some(target), Some(style)) = (vars.style.as_ref(), style.as_ref()) {
+                for field in target.unmeasured(style) {
+                    warn!(
+                        "The {} of {:?} can't be measured as Auto and will switch at the end",
+                        field, entity
+                    );
+                }
+            }
+            let mut source_transform = transform.as_ref().map(|transform| **transform);
+            if let (Blend::Additive, Some(source)) = (vars.blend, source_transform.as_mut()) {
+                // Additive channels measure their offset from the base value, not from the other offsets.
+                let mut layer = Layer::new::<Transform>(&mut layers, entity, animation.id);
+                let targeted = Targeted::new(vars, node.is_some() && style.is_some());
+                transform::base(&mut layer, targeted, source);
+            }
+            let mut source = Source {
+                style: style.as_ref().map(|style| (**style).clone()),
+                color: color.as_ref().map(|color| **color),
+                transform: source_transform,
+                text: text.as_ref().map(|text| (**text).clone()),
+                sprite: sprite.as_ref().map(|sprite| Sprite {
+                    custom_size: sprite.custom_size.or_else(|| {
+                        let image = images.as_ref()?.get(handle?)?;
+                        Some(image.size())
+                    }),
+                    ..(**sprite).clone()
+                }),
+                atlas: atlas.as_ref().map(|atlas| (**atlas).clone()),
+                layout: Layout {
+                    node: node.map_or(Vec2::ZERO, |node| node.size),
+                    parent: parent
+                        .and_then(|parent| nodes.get(parent.0).ok())
+                        .map_or(window_size, |node| node.size),
+                },
+                group_opacity: group_opacity.as_ref().map_or(1.0, |group| group.0),
+                duration: animation.playhead.duration(),
+                staggered: 0,
+            };
+            if let Some(ref text) = source.text {
+                let selections = [
+                    vars.text_color.as_ref().map(|t| &t.sections),
+                    vars.text_font_size.as_ref().map(|t| &t.sections),
+                ];
+                source.staggered = selections
+                    .into_iter()
+                    .flatten()
+                    .map(|sections| sections.indices(text.sections.len()).len())
+                    .max()
+                    .unwrap_or(0);
+                let duration = vars.text_reveal.as_ref().and_then(|reveal| {
+                    let indices = reveal.sections.indices(text.sections.len());
+                    let sources: Vec<_> = indices
+                        .into_iter()
+                        .map(|index| text.sections[index].value.as_str())
+                        .collect();
+                    reveal.duration(&sources)
+                });
+                let stagger = vars.stagger * source.staggered.saturating_sub(1) as f32;
+                // Set before the first tick, so an animation without duration still reveals.
+                if duration.is_some() || stagger > 0.0 {
+                    source.duration = duration.unwrap_or(source.duration);
+                    animation.playhead.set_duration(source.duration + stagger);
+                }
+            }
+            animation.source = Some(source);
+        }
+        if animation.playhead.delayed() {
+            if let (Some(reveal), Some(text), Some(source)) = (
+                animation.vars.text_reveal.as_ref(),
+                text.as_mut(),
+                animation
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.text.as_ref()),
+            ) {
+                reveal.apply(source, text, 0.0, time.seconds_since_startup());
+            }
+        }
+        if !started {
+            layers.hold(entity, animation.id);
+        } else {
+            animation.playhead.advance(time.delta());
+            if let (true, Some(entry)) =
+                (animation.playhead.has_duration(), animation.source.as_mut())
+            {
+                let vars = &animation.vars;
+                let (duration, count) = (entry.duration, entry.staggered);
+                let stagger = |progress, position| {
+                    staggered(progress, duration, vars.stagger, position, count)
+                };
+                // Only the text sections are staggered, the other channels play like the first one.
+                let progress = stagger(animation.playhead.progress(), 0);
+                let percent = stagger(animation.playhead.percent(), 0);
+                let elapsed = animation.playhead.elapsed().min(duration);
+                let delta = progress.delta(vars.ease);
+                let section_delta =
+                    |position| stagger(animation.playhead.progress(), position).delta(vars.ease);
+                if let Some(ref target) = vars.style {
+                    if let (Some(style), Some(source)) = (style.as_mut(), entry.style.as_ref()) {
+                        target.apply(source, &entry.layout, style, progress, delta);
                     }
-                    if let Some(ref target) = animation.vars.transform {
-                        *transform = entry.2.lerp(target, delta);
+                }
+                if let Some(ref target) = vars.color {
+                    if let (Some(color), Some(source)) = (color.as_mut(), entry.color) {
+                        color.0 = color::lerp(source.0, target.0, delta, vars.color_space);
                     }
-                    if let Some(ref target) = animation.vars.transform_rotation {
-                        let source_angle = entry.2.rotation.to_axis_angle().1;
-                        let target_angle = target.degree.to_radians();
-                        let delta_angle = source_angle + (target_angle - source_angle) * delta;
-                        transform.rotation = Quat::from_axis_angle(target.axis, delta_angle);
+                }
+                if let (Some(transform), Some(source)) = (transform.as_mut(), entry.transform) {
+                    let live = **transform;
+                    let shake = vars
+                        .shake
+                        .as_ref()
+                        .map(|shake| shake.offset(elapsed, percent));
+                    let size = node.map(|node| node.size).or_else(|| {
+                        let current = sprite.as_ref().and_then(|sprite| sprite.custom_size);
+                        current.or(entry.sprite.as_ref()?.custom_size)
+                    });
+                    if let Some(offset) =
+                        transform::apply(vars, &source, transform, delta, shake, size)
+                    {
+                        let style = entry.style.as_ref().zip(style.as_deref_mut());
+                        let style = style.filter(|_| node.is_some());
+                        let parent = entry.layout.parent;
+                        transform::translate(vars, offset, &source, transform, style, parent);
                     }
-                    if let Some(ref text_color) = animation.vars.text_color {
-                        if let Some(mut text) = text {
-                            if let Some(ref source) = entry.3 {
-                                let target: Vec4 = text_color.target.into();
-                                let source: Vec4 =
-                                    source.sections[text_color.section].style.color.into();
-                                let value = source.lerp(target, delta);
-                                text.sections[text_color.section].style.color = value.into();
-                            }
-                        }
+                    if vars.blend == Blend::Additive {
+                        let mut layer = Layer::new::<Transform>(&mut layers, entity, animation.id);
+                        let targeted = Targeted::new(vars, node.is_some() && style.is_some());
+                        transform::add(&mut layer, targeted, &live, &source, transform);
                     }
                 }
-                if animation.timer.just_finished() {
-                    if animation.vars.repeat {
-                        if animation.vars.yoyo {
-                            animation.direction *= -1;
-                        }
-                        animation.timer.reset();
-                    } else {
-                        commands.entity(entity).remove::<Animation>();
-                        if let Entry::Occupied(o) = source.entry(entity.id()) {
-                            o.remove_entry();
+                if let (Some(text), Some(source)) = (text.as_mut(), entry.text.as_ref()) {
+                    if let Some(ref text_color) = vars.text_color {
+                        text_color.apply(source, text, section_delta, vars.color_space);
+                    }
+                    if let Some(ref font_size) = vars.text_font_size {
+                        font_size.apply(source, text, section_delta);
+                    }
+                    if let Some(ref counter) = vars.text_counter {
+                        counter.apply(text, delta);
+                    }
+                    if let Some(ref reveal) = vars.text_reveal {
+                        // A rate of characters per second stays constant, whatever the ease.
+                        let delta = match reveal.chars_per_second {
+                            Some(_) => progress,
+                            None => delta,
+                        };
+                        reveal.apply(source, text, delta, time.seconds_since_startup());
+                    }
+                    if let Some(ref scramble) = vars.text_scramble {
+                        scramble.apply(source, text, delta, elapsed);
+                    }
+                }
+                if let Some(ref gradient) = vars.gradient {
+                    gradient.apply(
+                        delta,
+                        vars.color_space,
+                        color.as_deref_mut(),
+                        text.as_deref_mut(),
+                        sprite.as_deref_mut(),
+                        atlas.as_deref_mut(),
+                    );
+                }
+                if let Some(ref target) = vars.sprite {
+                    if let (Some(sprite), Some(source)) = (sprite.as_mut(), entry.sprite.as_ref()) {
+                        target.apply(source, sprite, progress, delta, vars.color_space);
+                    }
+                }
+                if let Some(ref target) = vars.atlas {
+                    if let (Some(atlas), Some(source)) = (atlas.as_mut(), entry.atlas.as_ref()) {
+                        target.apply(source, atlas, delta, vars.color_space);
+                    }
+                }
+                if let (Some(frames), Some(image)) = (vars.frames.as_ref(), image.as_mut()) {
+                    frames.apply(image, progress);
+                }
+                if let Some(ref opacity) = vars.opacity {
+                    opacity.apply(
+                        delta,
+                        entry.color.zip(color.as_deref_mut()),
+                        entry.text.as_ref().zip(text.as_deref_mut()),
+                        entry
+                            .style
+                            .as_ref()
+                            .map(|source| source.display)
+                            .zip(style.as_deref_mut()),
+                        visibility.as_deref_mut(),
+                    );
+                }
+                if let Some(target) = vars.group_opacity {
+                    let value = entry.group_opacity.lerp(&target, delta);
+                    match group_opacity {
+                        Some(mut group) => group.0 = value,
+                        None => {
+                            commands.entity(entity).insert(GroupOpacity(value));
                         }
                     }
-                    complete_event_writer.send(CompleteEvent { entity });
                 }
             }
+            if animation.playhead.just_finished() {
+                if animation.vars.text_reveal.is_some() && animation.playhead.forward() {
+                    reveal_event_writer.send(TextRevealEvent { entity });
+                }
+                if animation.vars.repeat {
+                    let yoyo = animation.vars.yoyo;
+                    animation.playhead.restart(yoyo);
+                } else {
+                    commands.entity(entity).remove::<Animation>();
+                }
+                complete_event_writer.send(CompleteEvent { entity });
+            }
+        }
+    }
+}
+
+/// Drives the [`Tweens`] of the component `C`.
+///
+/// Added by [`AnimationPlugin`] for [`Style`], [`UiColor`], [`Transform`] and [`Text`],
+/// after the [`Animation`]s so a tween wins over an animation writing the same property.
+/// Register it for any other component to tween its properties.
+pub fn tween_system<C: Component>(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut C, &mut Tweens<C>)>,
+    mut layers: ResMut<Layers>,
+    mut complete_event_writer: EventWriter<CompleteEvent>,
+) {
+    for (entity, mut component, mut tweens) in query.iter_mut() {
+        let mut completed = Vec::new();
+        // The replacing tweens set the base values the additive ones add onto.
+        let mut order: Vec<_> = (0..tweens.0.len()).collect();
+        order.sort_by_key(|&index| tweens.0[index].blend == Blend::Additive);
+        for index in order {
+            let tween = &mut tweens.0[index];
+            if tween.paused || !tween.playhead.tick(time.delta()) {
+                layers.hold(entity, tween.id);
+                continue;
+            }
+            if !tween.captured {
+                tween.capture(&component);
+            }
+            if tween.playhead.has_duration() {
+                let delta = tween.playhead.progress().delta(tween.ease);
+                tween.apply(&mut component, &mut layers, entity, delta);
+            }
+            if tween.playhead.just_finished() {
+                if tween.repeat {
+                    tween.playhead.restart(tween.yoyo);
+                } else {
+                    completed.push(index);
+                }
+                complete_event_writer.send(CompleteEvent { entity });
+            }
+        }
+        completed.sort_unstable();
+        for index in completed.into_iter().rev() {
+            tweens.0.remove(index);
+        }
+        if tweens.0.is_empty() {
+            commands.entity(entity).remove::<Tweens<C>>();
         }
     }
 }
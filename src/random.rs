@@ -0,0 +1,56 @@
+/// A SplitMix64 generator, small and deterministic enough for seeded effects.
+pub(crate) struct Rng(u64);
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// A random value in `[0.0, 1.0)`.
+    pub(crate) fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1 << 24) as f32
+    }
+    /// A random index below `n`, which must not be `0`.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    /// Shuffles `slice` in place with the Fisher-Yates algorithm.
+    pub(crate) fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.below(i + 1);
+            slice.swap(i, j);
+        }
+    }
+}
+
+/// Combines a seed with a value into a new seed.
+pub(crate) fn hash(seed: u64, value: u64) -> u64 {
+    Rng::new(seed ^ value.wrapping_mul(0x9e37_79b9_7f4a_7c15)).next_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng() {
+        let values: Vec<_> = (0..4).map(|_| Rng::new(7).next_u64()).collect();
+        assert!(values.windows(2).all(|pair| pair[0] == pair[1]));
+        assert_ne!(hash(7, 0), hash(7, 1));
+        assert_ne!(hash(7, 0), hash(8, 0));
+
+        let mut rng = Rng::new(7);
+        assert!((0..100).all(|_| rng.below(3) < 3));
+        assert!((0..100).all(|_| (0.0..1.0).contains(&rng.next_f32())));
+        let mut slice: Vec<_> = (0..10).collect();
+        rng.shuffle(&mut slice);
+        assert_ne!(slice, (0..10).collect::<Vec<_>>());
+        slice.sort_unstable();
+        assert_eq!(slice, (0..10).collect::<Vec<_>>());
+    }
+}
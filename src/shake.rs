@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::{
+    ease::Delta,
+    random::{hash, Rng},
+    Ease,
+};
+
+/// Shakes the translation and rotation of a [`Transform`], or the position of a UI node, with smooth noise.
+///
+/// The offsets are added on top of the other channels and fade out along the animation, so the entity always
+/// ends exactly where it started. A node anchored by `right` or `bottom` is shaken through those sides, and an
+/// axis anchored by neither through `left` or `top`, which return to their source once the shake ends.
+pub struct Shake {
+    /// Maximum offset in pixels.
+    pub amplitude: f32,
+    /// Multiplies the offset on each axis, [`Vec2::X`] only shaking horizontally.
+    pub axes: Vec2,
+    /// Maximum rotation around the Z axis in degrees.
+    pub rotation: f32,
+    /// Noise samples per second, higher values shaking faster.
+    pub frequency: f32,
+    /// How the amplitude decays over the animation, [`Ease::Linear`] fading it out steadily.
+    pub decay: Ease,
+    /// Seed of the noise, the same seed always shaking the same way.
+    pub seed: u64,
+}
+impl Default for Shake {
+    fn default() -> Self {
+        Self {
+            amplitude: 10.0,
+            axes: Vec2::ONE,
+            rotation: 0.0,
+            frequency: 20.0,
+            decay: Ease::Linear,
+            seed: 0,
+        }
+    }
+}
+impl Shake {
+    /// The offset in pixels and the rotation in degrees after `time` seconds, `percent` into the animation.
+    pub(crate) fn offset(&self, time: f32, percent: f32) -> (Vec2, f32) {
+        if percent >= 1.0 {
+            return (Vec2::ZERO, 0.0);
+        }
+        let envelope = 1.0 - percent.delta(self.decay);
+        let time = time * self.frequency;
+        let noise = |axis| noise(hash(self.seed, axis), time) * envelope;
+        let offset = Vec2::new(noise(0), noise(1)) * self.axes * self.amplitude;
+        (offset, noise(2) * self.rotation)
+    }
+}
+
+/// Smooth value noise in `[-1.0, 1.0]`, starting from `0.0`.
+fn noise(seed: u64, time: f32) -> f32 {
+    let value = |lattice: i64| match lattice {
+        0 => 0.0,
+        _ => Rng::new(hash(seed, lattice as u64)).next_f32() * 2.0 - 1.0,
+    };
+    let lattice = time.floor();
+    let fraction = time - lattice;
+    let (start, end) = (value(lattice as i64), value(lattice as i64 + 1));
+    start + (end - start) * fraction * fraction * (3.0 - 2.0 * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shake() {
+        let shake = Shake {
+            axes: Vec2::X,
+            rotation: 5.0,
+            ..Default::default()
+        };
+        assert_eq!(shake.offset(0.0, 0.0), (Vec2::ZERO, 0.0));
+        assert_eq!(shake.offset(0.3, 1.0), (Vec2::ZERO, 0.0));
+        assert_eq!(shake.offset(0.123, 0.4), shake.offset(0.123, 0.4));
+
+        let (offset, rotation) = shake.offset(0.123, 0.4);
+        assert_eq!(offset.y, 0.0);
+        assert!(offset.x != 0.0 && offset.x.abs() <= 6.0);
+        assert!(rotation != 0.0 && rotation.abs() <= 3.0);
+
+        let (other, _) = Shake { seed: 1, ..shake }.offset(0.123, 0.4);
+        assert_ne!(offset, other);
+    }
+
+    #[test]
+    fn smooth() {
+        for step in 0..100 {
+            let time = step as f32 * 0.05;
+            let value = noise(7, time);
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((noise(7, time + 0.01) - value).abs() < 0.1);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::{
+    color::{self, ColorSpace},
+    lerp::Lerp,
+};
+
+/// Manipulates the fields of a [`Sprite`] component.
+#[derive(Clone, Copy, Default)]
+pub struct SpriteTarget {
+    /// Target color.
+    pub color: Option<Color>,
+    /// Target custom size, starting from the size of the image if the sprite has none.
+    pub custom_size: Option<Vec2>,
+}
+impl SpriteTarget {
+    /// Interpolates the fields of `sprite` from `source`, a size without source switching at the end.
+    pub(crate) fn apply(
+        &self,
+        source: &Sprite,
+        sprite: &mut Sprite,
+        progress: f32,
+        delta: f32,
+        space: ColorSpace,
+    ) {
+        if let Some(color) = self.color {
+            sprite.color = color::lerp(source.color, color, delta, space);
+        }
+        if let Some(size) = self.custom_size {
+            sprite.custom_size = match source.custom_size {
+                Some(source) => Some(source.lerp(&size, delta)),
+                None if progress >= 1.0 => Some(size),
+                None => None,
+            };
+        }
+    }
+}
+
+/// Manipulates the fields of a [`TextureAtlasSprite`] component.
+#[derive(Clone, Copy, Default)]
+pub struct AtlasTarget {
+    /// Target index, stepping through every index in between.
+    pub index: Option<usize>,
+    /// Target color.
+    pub color: Option<Color>,
+}
+impl AtlasTarget {
+    /// Interpolates the fields of `atlas` from `source`.
+    pub(crate) fn apply(
+        &self,
+        source: &TextureAtlasSprite,
+        atlas: &mut TextureAtlasSprite,
+        delta: f32,
+        space: ColorSpace,
+    ) {
+        if let Some(index) = self.index {
+            atlas.index = step_index(source.index, index, delta);
+        }
+        if let Some(color) = self.color {
+            atlas.color = color::lerp(source.color, color, delta, space);
+        }
+    }
+}
+
+/// The index stepped to at `delta` between the `source` and `target` indices.
+fn step_index(source: usize, target: usize, delta: f32) -> usize {
+    let index = source as f32 + (target as f32 - source as f32) * delta;
+    index.round().max(0.0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index() {
+        assert_eq!(step_index(0, 8, 0.0), 0);
+        assert_eq!(step_index(0, 8, 0.3), 2);
+        assert_eq!(step_index(0, 8, 1.0), 8);
+        assert_eq!(step_index(5, 1, 0.5), 3);
+        assert_eq!(step_index(0, 3, -0.5), 0);
+    }
+}
@@ -0,0 +1,362 @@
+use bevy::prelude::*;
+
+use crate::lerp::Lerp;
+
+/// The destination fields of a [`Style`] component to animate to.
+///
+/// Fields left as `None` are never written, so a target only touches what it explicitly specifies.
+/// Values of different [`Val`] variants are resolved against the computed size of the node and its parent,
+/// interpolated in pixels and switched to the target's unit once the animation ends.
+/// `Val::Auto` and `Val::Undefined` sizes resolve to the size of the node when the animation starts,
+/// so a size animating to them can't be measured beforehand and switches once the animation ends.
+/// Fields that can't be interpolated switch at the progress given by their [`Discrete`] value.
+#[derive(Clone, Copy, Default)]
+pub struct StyleTarget {
+    /// Target display, switched at its progress.
+    pub display: Option<Discrete<Display>>,
+    /// Target position type, switched at its progress.
+    pub position_type: Option<Discrete<PositionType>>,
+    /// Target flex direction, switched at its progress.
+    pub flex_direction: Option<Discrete<FlexDirection>>,
+    /// Target alignment of the children on the cross axis, switched at its progress.
+    pub align_items: Option<Discrete<AlignItems>>,
+    /// Target alignment of the node on the cross axis of its parent, switched at its progress.
+    pub align_self: Option<Discrete<AlignSelf>>,
+    /// Target alignment of the lines on the cross axis, switched at its progress.
+    pub align_content: Option<Discrete<AlignContent>>,
+    /// Target alignment of the children on the main axis, switched at its progress.
+    pub justify_content: Option<Discrete<JustifyContent>>,
+    /// Target overflow, switched at its progress.
+    pub overflow: Option<Discrete<Overflow>>,
+    /// Target position.
+    pub position: Option<Rect<Val>>,
+    /// Target margin.
+    pub margin: Option<Rect<Val>>,
+    /// Target padding.
+    pub padding: Option<Rect<Val>>,
+    /// Target border.
+    pub border: Option<Rect<Val>>,
+    /// Target flex grow factor.
+    pub flex_grow: Option<f32>,
+    /// Target flex shrink factor.
+    pub flex_shrink: Option<f32>,
+    /// Target flex basis.
+    pub flex_basis: Option<Val>,
+    /// Target size.
+    pub size: Option<Size<Val>>,
+    /// Target minimum size.
+    pub min_size: Option<Size<Val>>,
+    /// Target maximum size.
+    pub max_size: Option<Size<Val>>,
+    /// Target aspect ratio, switched at the end if the node has none.
+    pub aspect_ratio: Option<f32>,
+}
+impl StyleTarget {
+    pub(crate) fn apply(
+        &self,
+        source: &Style,
+        layout: &Layout,
+        style: &mut Style,
+        progress: f32,
+        delta: f32,
+    ) {
+        let lerp = |source: Val, target: Val, parent: f32, computed: f32| match (source, target) {
+            (Val::Px(_), Val::Px(_)) | (Val::Percent(_), Val::Percent(_)) => {
+                source.lerp(&target, delta)
+            }
+            _ if source == target || progress >= 1.0 => target,
+            _ if progress <= 0.0 => source,
+            _ => Val::Px(
+                resolve(source, parent, computed).lerp(&resolve(target, parent, computed), delta),
+            ),
+        };
+        let lerp_rect = |source: Rect<Val>, target: Rect<Val>, vertical: f32| Rect {
+            left: lerp(source.left, target.left, layout.parent.x, 0.0),
+            right: lerp(source.right, target.right, layout.parent.x, 0.0),
+            top: lerp(source.top, target.top, vertical, 0.0),
+            bottom: lerp(source.bottom, target.bottom, vertical, 0.0),
+        };
+        let lerp_size = |source: Size<Val>, target: Size<Val>| {
+            Size::new(
+                lerp(source.width, target.width, layout.parent.x, layout.node.x),
+                lerp(source.height, target.height, layout.parent.y, layout.node.y),
+            )
+        };
+        if let Some(target) = self.display {
+            style.display = target.apply(source.display, progress);
+        }
+        if let Some(target) = self.position_type {
+            style.position_type = target.apply(source.position_type, progress);
+        }
+        if let Some(target) = self.flex_direction {
+            style.flex_direction = target.apply(source.flex_direction, progress);
+        }
+        if let Some(target) = self.align_items {
+            style.align_items = target.apply(source.align_items, progress);
+        }
+        if let Some(target) = self.align_self {
+            style.align_self = target.apply(source.align_self, progress);
+        }
+        if let Some(target) = self.align_content {
+            style.align_content = target.apply(source.align_content, progress);
+        }
+        if let Some(target) = self.justify_content {
+            style.justify_content = target.apply(source.justify_content, progress);
+        }
+        if let Some(target) = self.overflow {
+            style.overflow = target.apply(source.overflow, progress);
+        }
+        if let Some(target) = self.position {
+            style.position = lerp_rect(source.position, target, layout.parent.y);
+        }
+        if let Some(target) = self.margin {
+            style.margin = lerp_rect(source.margin, target, layout.parent.x);
+        }
+        if let Some(target) = self.padding {
+            style.padding = lerp_rect(source.padding, target, layout.parent.x);
+        }
+        if let Some(target) = self.border {
+            style.border = lerp_rect(source.border, target, layout.parent.x);
+        }
+        if let Some(target) = self.flex_grow {
+            style.flex_grow = source.flex_grow.lerp(&target, delta);
+        }
+        if let Some(target) = self.flex_shrink {
+            style.flex_shrink = source.flex_shrink.lerp(&target, delta);
+        }
+        if let Some(target) = self.flex_basis {
+            style.flex_basis = lerp(source.flex_basis, target, layout.parent.x, layout.node.x);
+        }
+        if let Some(target) = self.size {
+            style.size = lerp_size(source.size, target);
+        }
+        if let Some(target) = self.min_size {
+            style.min_size = lerp_size(source.min_size, target);
+        }
+        if let Some(target) = self.max_size {
+            style.max_size = lerp_size(source.max_size, target);
+        }
+        if let Some(target) = self.aspect_ratio {
+            style.aspect_ratio = match source.aspect_ratio {
+                Some(source) => Some(source.lerp(&target, delta)),
+                None if progress >= 1.0 => Some(target),
+                None => None,
+            };
+        }
+    }
+    /// The size fields animating from a length to `Val::Auto` or `Val::Undefined`, which switch at the end.
+    pub(crate) fn unmeasured(&self, source: &Style) -> Vec<&'static str> {
+        let to_auto = |source: Val, target: Val| {
+            !matches!(source, Val::Auto | Val::Undefined)
+                && matches!(target, Val::Auto | Val::Undefined)
+        };
+        let sizes = [
+            ("size", source.size, self.size),
+            ("min_size", source.min_size, self.min_size),
+            ("max_size", source.max_size, self.max_size),
+        ];
+        let mut fields = Vec::new();
+        for (field, source, target) in sizes {
+            if let Some(target) = target {
+                if to_auto(source.width, target.width) || to_auto(source.height, target.height) {
+                    fields.push(field);
+                }
+            }
+        }
+        if let Some(target) = self.flex_basis {
+            if to_auto(source.flex_basis, target) {
+                fields.push("flex_basis");
+            }
+        }
+        fields
+    }
+}
+
+/// A value that can't be interpolated, switching from the source to the target at a given progress.
+#[derive(Clone, Copy)]
+pub struct Discrete<T> {
+    /// Target value.
+    pub value: T,
+    /// Progress of the animation, from `0.0` to `1.0`, at which the value is switched.
+    pub at: f32,
+}
+impl<T: Copy> Discrete<T> {
+    /// Switches to `value` as soon as the animation starts.
+    pub fn start(value: T) -> Self {
+        Self { value, at: 0.0 }
+    }
+    /// Switches to `value` once the animation ends.
+    pub fn end(value: T) -> Self {
+        Self { value, at: 1.0 }
+    }
+    /// Switches to `value` once the progress of the animation reaches `at`.
+    pub fn at(value: T, at: f32) -> Self {
+        Self { value, at }
+    }
+    pub(crate) fn apply(&self, source: T, progress: f32) -> T {
+        if progress >= self.at {
+            self.value
+        } else {
+            source
+        }
+    }
+}
+
+/// The computed sizes relative [`Val`]s are resolved against.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Layout {
+    /// The computed size of the node itself.
+    pub(crate) node: Vec2,
+    /// The computed size of the parent node, or of the window for root nodes.
+    pub(crate) parent: Vec2,
+}
+
+/// Converts a [`Val`] to pixels, where `computed` stands in for `Val::Auto` and `Val::Undefined`.
+fn resolve(val: Val, parent: f32, computed: f32) -> f32 {
+    match val {
+        Val::Px(px) => px,
+        Val::Percent(percent) => parent * percent / 100.0,
+        Val::Auto | Val::Undefined => computed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial() {
+        let source = Style {
+            margin: Rect::all(Val::Px(10.0)),
+            size: Size::new(Val::Px(0.0), Val::Px(0.0)),
+            ..Default::default()
+        };
+        let target = StyleTarget {
+            size: Some(Size::new(Val::Px(100.0), Val::Px(50.0))),
+            ..Default::default()
+        };
+
+        let mut style = source;
+        target.apply(&source, &Layout::default(), &mut style, 0.5, 0.5);
+        assert_eq!(style.size, Size::new(Val::Px(50.0), Val::Px(25.0)));
+        assert_eq!(style.margin, Rect::all(Val::Px(10.0)));
+        assert_eq!(style.position, Rect::all(Val::Undefined));
+        assert_eq!(style.flex_grow, 0.0);
+    }
+
+    #[test]
+    fn flex() {
+        let source = Style {
+            flex_grow: 0.0,
+            flex_basis: Val::Percent(0.0),
+            min_size: Size::new(Val::Px(0.0), Val::Px(0.0)),
+            ..Default::default()
+        };
+        let target = StyleTarget {
+            flex_grow: Some(1.0),
+            flex_basis: Some(Val::Percent(50.0)),
+            min_size: Some(Size::new(Val::Px(10.0), Val::Px(20.0))),
+            aspect_ratio: Some(2.0),
+            ..Default::default()
+        };
+
+        let mut style = source;
+        target.apply(&source, &Layout::default(), &mut style, 0.5, 0.5);
+        assert_eq!(style.flex_grow, 0.5);
+        assert_eq!(style.flex_basis, Val::Percent(25.0));
+        assert_eq!(style.min_size, Size::new(Val::Px(5.0), Val::Px(10.0)));
+        assert_eq!(style.aspect_ratio, None);
+        target.apply(&source, &Layout::default(), &mut style, 1.0, 1.0);
+        assert_eq!(style.aspect_ratio, Some(2.0));
+    }
+
+    #[test]
+    fn mismatched() {
+        let source = Style {
+            margin: Rect::all(Val::Px(10.0)),
+            ..Default::default()
+        };
+        let target = StyleTarget {
+            margin: Some(Rect::all(Val::Undefined)),
+            ..Default::default()
+        };
+
+        let mut style = source;
+        target.apply(&source, &Layout::default(), &mut style, 0.5, 0.5);
+        assert_eq!(style.margin, Rect::all(Val::Px(5.0)));
+        target.apply(&source, &Layout::default(), &mut style, 1.0, 1.0);
+        assert_eq!(style.margin, Rect::all(Val::Undefined));
+        target.apply(&source, &Layout::default(), &mut style, 0.0, 0.0);
+        assert_eq!(style.margin, Rect::all(Val::Px(10.0)));
+    }
+
+    #[test]
+    fn layout() {
+        let source = Style {
+            size: Size::new(Val::Percent(100.0), Val::Auto),
+            ..Default::default()
+        };
+        let target = StyleTarget {
+            size: Some(Size::new(Val::Px(300.0), Val::Px(100.0))),
+            ..Default::default()
+        };
+        let layout = Layout {
+            node: Vec2::new(400.0, 300.0),
+            parent: Vec2::new(400.0, 600.0),
+        };
+
+        let mut style = source;
+        target.apply(&source, &layout, &mut style, 0.5, 0.5);
+        assert_eq!(style.size, Size::new(Val::Px(350.0), Val::Px(200.0)));
+        target.apply(&source, &layout, &mut style, 1.0, 1.0);
+        assert_eq!(style.size, Size::new(Val::Px(300.0), Val::Px(100.0)));
+        target.apply(&source, &layout, &mut style, 0.0, 0.0);
+        assert_eq!(style.size, Size::new(Val::Percent(100.0), Val::Auto));
+    }
+
+    #[test]
+    fn unmeasured() {
+        let source = Style {
+            size: Size::new(Val::Px(200.0), Val::Px(0.0)),
+            ..Default::default()
+        };
+        let target = StyleTarget {
+            size: Some(Size::new(Val::Px(200.0), Val::Auto)),
+            min_size: Some(Size::new(Val::Auto, Val::Auto)),
+            ..Default::default()
+        };
+        assert_eq!(target.unmeasured(&source), ["size"]);
+
+        let layout = Layout {
+            node: Vec2::new(200.0, 0.0),
+            parent: Vec2::new(400.0, 600.0),
+        };
+        let mut style = source;
+        target.apply(&source, &layout, &mut style, 0.5, 0.5);
+        assert_eq!(style.size.height, Val::Px(0.0));
+        target.apply(&source, &layout, &mut style, 1.0, 1.0);
+        assert_eq!(style.size.height, Val::Auto);
+    }
+
+    #[test]
+    fn discrete() {
+        let source = Style::default();
+        let target = StyleTarget {
+            display: Some(Discrete::end(Display::None)),
+            flex_direction: Some(Discrete::at(FlexDirection::Column, 0.5)),
+            justify_content: Some(Discrete::start(JustifyContent::Center)),
+            ..Default::default()
+        };
+
+        let mut style = source;
+        target.apply(&source, &Layout::default(), &mut style, 0.25, 0.25);
+        assert_eq!(style.display, Display::Flex);
+        assert_eq!(style.flex_direction, FlexDirection::Row);
+        assert_eq!(style.justify_content, JustifyContent::Center);
+        target.apply(&source, &Layout::default(), &mut style, 0.5, 0.5);
+        assert_eq!(style.display, Display::Flex);
+        assert_eq!(style.flex_direction, FlexDirection::Column);
+        target.apply(&source, &Layout::default(), &mut style, 1.0, 1.0);
+        assert_eq!(style.display, Display::None);
+    }
+}
@@ -0,0 +1,749 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+
+use crate::{
+    color::{self, ColorSpace},
+    lerp::Lerp,
+    random::{hash, Rng},
+    Animation,
+};
+
+/// Selects the sections of a [`Text`] component to animate.
+#[derive(Clone, Debug, Default)]
+pub enum TextSections {
+    /// Every section.
+    #[default]
+    All,
+    /// The sections at the given indices.
+    List(Vec<usize>),
+    /// The sections within the given range of indices.
+    Range(Range<usize>),
+}
+impl TextSections {
+    /// The selected indices for a text of `len` sections, skipping the ones out of bounds.
+    pub(crate) fn indices(&self, len: usize) -> Vec<usize> {
+        match self {
+            Self::All => (0..len).collect(),
+            Self::List(indices) => indices.iter().copied().filter(|&i| i < len).collect(),
+            Self::Range(range) => (range.start..range.end.min(len)).collect(),
+        }
+    }
+    /// The first selected index out of bounds for a text of `len` sections.
+    pub(crate) fn out_of_bounds(&self, len: usize) -> Option<usize> {
+        match self {
+            Self::All => None,
+            Self::List(indices) => indices.iter().copied().find(|&i| i >= len),
+            Self::Range(range) => (range.end > len).then(|| len.max(range.start)),
+        }
+    }
+}
+impl From<usize> for TextSections {
+    fn from(index: usize) -> Self {
+        Self::List(vec![index])
+    }
+}
+impl From<Vec<usize>> for TextSections {
+    fn from(indices: Vec<usize>) -> Self {
+        Self::List(indices)
+    }
+}
+impl From<Range<usize>> for TextSections {
+    fn from(range: Range<usize>) -> Self {
+        Self::Range(range)
+    }
+}
+
+/// Manipulates the color field of the sections of a [`Text`] component.
+pub struct TextColor {
+    /// Target color.
+    pub target: Color,
+    /// Sections of the [`Text`] component to animate.
+    pub sections: TextSections,
+    /// If `true`, only the alpha is animated, so rich text keeps its relative colors when fading.
+    pub alpha_only: bool,
+}
+impl Default for TextColor {
+    fn default() -> Self {
+        Self {
+            target: Color::WHITE,
+            sections: TextSections::All,
+            alpha_only: false,
+        }
+    }
+}
+impl TextColor {
+    /// Interpolates the color of the selected sections from `source`, at the delta of their position.
+    pub(crate) fn apply(
+        &self,
+        source: &Text,
+        text: &mut Text,
+        delta: impl Fn(usize) -> f32,
+        space: ColorSpace,
+    ) {
+        let indices = self.sections.indices(text.sections.len());
+        for (position, index) in indices.into_iter().enumerate() {
+            if let (Some(section), Some(source)) =
+                (text.sections.get_mut(index), source.sections.get(index))
+            {
+                let delta = delta(position);
+                let source = source.style.color;
+                section.style.color = if self.alpha_only {
+                    let mut value = source;
+                    value.set_a(source.a().lerp(&self.target.a(), delta));
+                    value
+                } else {
+                    color::lerp(source, self.target, delta, space)
+                };
+            }
+        }
+    }
+}
+
+/// Manipulates the font size of the sections of a [`Text`] component.
+///
+/// The text isn't offset to keep its [`TextAlignment`] anchor in place, it grows wherever the layout puts it.
+pub struct TextFontSize {
+    /// Target font size.
+    pub target: f32,
+    /// Sections of the [`Text`] component to animate.
+    pub sections: TextSections,
+}
+impl TextFontSize {
+    /// Interpolates the font size of the selected sections from `source`, at the delta of their position.
+    pub(crate) fn apply(&self, source: &Text, text: &mut Text, delta: impl Fn(usize) -> f32) {
+        let indices = self.sections.indices(text.sections.len());
+        for (position, index) in indices.into_iter().enumerate() {
+            if let (Some(section), Some(source)) =
+                (text.sections.get_mut(index), source.sections.get(index))
+            {
+                section.style.font_size = source
+                    .style
+                    .font_size
+                    .lerp(&self.target, delta(position))
+                    .max(0.0);
+            }
+        }
+    }
+}
+
+/// Counts a number from `from` to `to`, writing it into the sections of a [`Text`] component.
+pub struct TextCounter {
+    /// Starting value.
+    pub from: f64,
+    /// Target value.
+    pub to: f64,
+    /// Sections of the [`Text`] component to write into.
+    pub sections: TextSections,
+    /// How the value is formatted.
+    pub format: NumberFormat,
+}
+impl TextCounter {
+    /// Writes the value counted at `delta` into the selected sections.
+    pub(crate) fn apply(&self, text: &mut Text, delta: f32) {
+        let value = self
+            .format
+            .format(self.from + (self.to - self.from) * delta as f64);
+        for index in self.sections.indices(text.sections.len()) {
+            if text.sections[index].value != value {
+                text.sections[index].value = value.clone();
+            }
+        }
+    }
+}
+
+/// Formats the value of a [`TextCounter`].
+#[derive(Clone, Default)]
+pub struct NumberFormat {
+    /// Number of decimal places.
+    pub decimals: usize,
+    /// Thousands separator of the integer part.
+    pub separator: Option<char>,
+    /// Text written before the number.
+    pub prefix: String,
+    /// Text written after the number.
+    pub suffix: String,
+    /// Formats the value instead of the fields above if set.
+    pub custom: Option<fn(f64) -> String>,
+}
+impl NumberFormat {
+    /// Formats `value`.
+    pub fn format(&self, value: f64) -> String {
+        if let Some(custom) = self.custom {
+            return custom(value);
+        }
+        let mut number = format!("{:.*}", self.decimals, value);
+        if number.starts_with('-') && number.trim_matches(&['-', '0', '.'][..]).is_empty() {
+            number.remove(0);
+        }
+        if let Some(separator) = self.separator {
+            let (sign, unsigned) = match number.strip_prefix('-') {
+                Some(unsigned) => ("-", unsigned),
+                None => ("", number.as_str()),
+            };
+            let (integer, fraction) = match unsigned.find('.') {
+                Some(dot) => unsigned.split_at(dot),
+                None => (unsigned, ""),
+            };
+            let mut grouped = String::new();
+            for (i, digit) in integer.chars().enumerate() {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    grouped.push(separator);
+                }
+                grouped.push(digit);
+            }
+            number = format!("{}{}{}", sign, grouped, fraction);
+        }
+        format!("{}{}{}", self.prefix, number, self.suffix)
+    }
+}
+
+/// Progressively reveals the strings of the sections of a [`Text`] component, one section after another.
+///
+/// The sections are emptied as soon as the [`Animation`] is inserted, delay included.
+pub struct TextReveal {
+    /// Sections of the [`Text`] component to reveal.
+    pub sections: TextSections,
+    /// Granularity of the reveal.
+    pub unit: TextUnit,
+    /// Cursor written after the revealed text while revealing.
+    pub cursor: Option<Cursor>,
+    /// If set, the duration of the animation is derived from the number of characters to reveal,
+    /// which are revealed at this constant rate whatever the ease.
+    pub chars_per_second: Option<f32>,
+}
+impl Default for TextReveal {
+    fn default() -> Self {
+        Self {
+            sections: TextSections::All,
+            unit: TextUnit::Character,
+            cursor: None,
+            chars_per_second: None,
+        }
+    }
+}
+impl TextReveal {
+    /// The duration needed to reveal `sources` at `chars_per_second`.
+    pub(crate) fn duration(&self, sources: &[&str]) -> Option<f32> {
+        let chars: usize = sources.iter().map(|source| source.chars().count()).sum();
+        self.chars_per_second
+            .filter(|&speed| speed > 0.0)
+            .map(|speed| chars as f32 / speed)
+    }
+    /// Writes the selected sections of `source` revealed at `delta` into `text`.
+    pub(crate) fn apply(&self, source: &Text, text: &mut Text, delta: f32, time: f64) {
+        let indices = self
+            .sections
+            .indices(text.sections.len().min(source.sections.len()));
+        let sources: Vec<_> = indices
+            .iter()
+            .map(|&index| source.sections[index].value.as_str())
+            .collect();
+        let values = self.reveal(&sources, delta, time);
+        for (index, value) in indices.into_iter().zip(values) {
+            if text.sections[index].value != value {
+                text.sections[index].value = value;
+            }
+        }
+    }
+    /// The strings of `sources` revealed at `delta`, showing the cursor if `time` falls in its on phase.
+    pub(crate) fn reveal(&self, sources: &[&str], delta: f32, time: f64) -> Vec<String> {
+        let ends: Vec<_> = sources
+            .iter()
+            .map(|source| boundaries(source, self.unit))
+            .collect();
+        let total: usize = ends.iter().map(Vec::len).sum();
+        let mut remaining = (total as f32 * delta.clamp(0.0, 1.0)).floor() as usize;
+        let mut cursor = self.cursor.as_ref().filter(|_| remaining < total);
+        sources
+            .iter()
+            .zip(ends)
+            .map(|(source, ends)| {
+                let count = remaining.min(ends.len());
+                remaining -= count;
+                let mut value = match count {
+                    0 => String::new(),
+                    count => source[..ends[count - 1]].to_string(),
+                };
+                if count < ends.len() {
+                    if let Some(cursor) = cursor.take() {
+                        if cursor.visible(time) {
+                            value.push_str(&cursor.glyph);
+                        }
+                    }
+                }
+                value
+            })
+            .collect()
+    }
+}
+
+/// Granularity of a [`TextReveal`] or of a [`SplitText`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextUnit {
+    /// One character at a time.
+    #[default]
+    Character,
+    /// One word at a time, along with the whitespace before it.
+    Word,
+    /// One line at a time.
+    Line,
+}
+
+/// Cursor of a [`TextReveal`].
+#[derive(Clone, Debug)]
+pub struct Cursor {
+    /// Text of the cursor.
+    pub glyph: String,
+    /// Seconds the cursor stays on, then off. `0.0` disables blinking.
+    pub blink: f32,
+}
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            glyph: "_".to_string(),
+            blink: 0.5,
+        }
+    }
+}
+impl Cursor {
+    fn visible(&self, time: f64) -> bool {
+        self.blink <= 0.0 || (time / (2.0 * self.blink as f64)).fract() < 0.5
+    }
+}
+
+/// The byte offsets at which each unit of `text` ends, the last one being the end of `text`.
+fn boundaries(text: &str, unit: TextUnit) -> Vec<usize> {
+    let mut ends: Vec<_> = match unit {
+        TextUnit::Character => text.char_indices().map(|(i, c)| i + c.len_utf8()).collect(),
+        TextUnit::Word => {
+            let mut chars = text.char_indices().peekable();
+            let mut ends = Vec::new();
+            while let Some((i, c)) = chars.next() {
+                let next = !matches!(chars.peek(), Some((_, next)) if !next.is_whitespace());
+                if !c.is_whitespace() && next {
+                    ends.push(i + c.len_utf8());
+                }
+            }
+            ends
+        }
+        TextUnit::Line => text.match_indices('\n').map(|(i, _)| i).collect(),
+    };
+    if !text.is_empty() && ends.last() != Some(&text.len()) {
+        ends.push(text.len());
+    }
+    ends
+}
+
+/// Splits the section at `index` of `text` into one section per `unit`, returning the range of the new sections.
+///
+/// Every new section keeps the style of the original one, so they can be animated with [`TextSections`].
+pub fn split_text(text: &mut Text, index: usize, unit: TextUnit) -> Range<usize> {
+    let section = match text.sections.get(index) {
+        Some(section) => section.clone(),
+        None => return index..index,
+    };
+    let mut start = 0;
+    let pieces: Vec<_> = boundaries(&section.value, unit)
+        .into_iter()
+        .map(|end| {
+            let value = section.value[start..end].to_string();
+            start = end;
+            TextSection {
+                value,
+                style: section.style.clone(),
+            }
+        })
+        .collect();
+    // An empty section has nothing to split, it's kept so merging gives it back.
+    if pieces.is_empty() {
+        return index..index + 1;
+    }
+    let range = index..index + pieces.len();
+    text.sections.splice(index..=index, pieces);
+    range
+}
+
+/// Merges the sections in `range` of `text` back into one section with the style of the first one.
+pub fn merge_text(text: &mut Text, range: Range<usize>) {
+    let range = range.start.min(text.sections.len())..range.end.min(text.sections.len());
+    if let Some(first) = text.sections.get(range.start) {
+        let section = TextSection {
+            value: text.sections[range.clone()]
+                .iter()
+                .map(|section| section.value.as_str())
+                .collect(),
+            style: first.style.clone(),
+        };
+        text.sections.splice(range, [section]);
+    }
+}
+
+/// Splits a section of the [`Text`] component of an entity into one section per unit while it's animated.
+///
+/// Insert it along with the [`Animation`], the sections are merged back once the animation is done.
+/// If it's inserted first, the sections stay split until an animation has been inserted and removed.
+#[derive(Component)]
+pub struct SplitText {
+    /// Index of the section to split.
+    pub section: usize,
+    /// Granularity of the split.
+    pub unit: TextUnit,
+    split: Option<Range<usize>>,
+    /// Whether an [`Animation`] has been seen on the entity since the split.
+    animated: bool,
+}
+impl SplitText {
+    /// Splits the section at `section` by `unit`.
+    pub fn new(section: usize, unit: TextUnit) -> Self {
+        Self {
+            section,
+            unit,
+            split: None,
+            animated: false,
+        }
+    }
+}
+
+pub(crate) fn split_text_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Text, &mut SplitText, Option<&Animation>)>,
+) {
+    for (entity, mut text, mut split, animation) in query.iter_mut() {
+        if split.split.is_none() {
+            split.split = Some(split_text(&mut text, split.section, split.unit));
+        }
+        split.animated |= animation.is_some();
+        if let (true, None, Some(range)) = (split.animated, animation, split.split.clone()) {
+            merge_text(&mut text, range);
+            commands.entity(entity).remove::<SplitText>();
+        }
+    }
+}
+
+/// The progress of the section at `position` out of `count` staggered sections.
+///
+/// Each section plays for `duration` seconds, `stagger` seconds after the previous one.
+pub(crate) fn staggered(
+    progress: f32,
+    duration: f32,
+    stagger: f32,
+    position: usize,
+    count: usize,
+) -> f32 {
+    let total = duration + stagger * count.saturating_sub(1) as f32;
+    let elapsed = progress * total - stagger * position as f32;
+    if duration > 0.0 {
+        (elapsed / duration).clamp(0.0, 1.0)
+    } else if elapsed >= 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Resolves random glyphs into a string in the sections of a [`Text`] component, like GSAP's ScrambleText.
+pub struct TextScramble {
+    /// String to resolve into, or the original string of each section if `None`.
+    pub target: Option<String>,
+    /// Sections of the [`Text`] component to scramble.
+    pub sections: TextSections,
+    /// Characters the random glyphs are picked from.
+    pub chars: String,
+    /// Order in which the characters resolve.
+    pub order: ScrambleOrder,
+    /// Seed of the random glyphs and order, the same seed always scrambling the same way.
+    pub seed: u64,
+    /// How many times per second the random glyphs change.
+    pub rate: f32,
+}
+impl Default for TextScramble {
+    fn default() -> Self {
+        Self {
+            target: None,
+            sections: TextSections::All,
+            chars: "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&*+-/<=>?".to_string(),
+            order: ScrambleOrder::LeftToRight,
+            seed: 0,
+            rate: 20.0,
+        }
+    }
+}
+impl TextScramble {
+    /// Scrambles the selected sections from `source` at `delta`, `elapsed` seconds into the animation.
+    pub(crate) fn apply(&self, source: &Text, text: &mut Text, delta: f32, elapsed: f32) {
+        let step = (elapsed * self.rate.max(0.0)) as u64;
+        let len = text.sections.len().min(source.sections.len());
+        for index in self.sections.indices(len) {
+            let original = &source.sections[index].value;
+            let target = self.target.as_deref().unwrap_or(original);
+            let value = self.scramble(original, target, delta, step, index as u64);
+            if text.sections[index].value != value {
+                text.sections[index].value = value;
+            }
+        }
+    }
+    /// The string scrambling `source` into `target` at `delta`.
+    ///
+    /// `step` selects the random glyphs and `salt` differentiates sections.
+    pub(crate) fn scramble(
+        &self,
+        source: &str,
+        target: &str,
+        delta: f32,
+        step: u64,
+        salt: u64,
+    ) -> String {
+        let delta = delta.clamp(0.0, 1.0);
+        let target: Vec<_> = target.chars().collect();
+        let chars: Vec<_> = self.chars.chars().collect();
+        let source_len = source.chars().count() as f32;
+        let len = (source_len + (target.len() as f32 - source_len) * delta).round() as usize;
+        let count = (target.len() as f32 * delta).floor() as usize;
+        let mut resolved = vec![false; target.len()];
+        match self.order {
+            ScrambleOrder::LeftToRight => resolved[..count].fill(true),
+            ScrambleOrder::Random => {
+                let mut order: Vec<_> = (0..target.len()).collect();
+                Rng::new(hash(self.seed, salt)).shuffle(&mut order);
+                for &index in &order[..count] {
+                    resolved[index] = true;
+                }
+            }
+        }
+        let mut rng = Rng::new(hash(hash(self.seed, salt), step));
+        (0..len)
+            .map(|index| match target.get(index) {
+                Some(&c) if resolved[index] || c.is_whitespace() => c,
+                _ if chars.is_empty() => ' ',
+                _ => chars[rng.below(chars.len())],
+            })
+            .collect()
+    }
+}
+
+/// Order in which a [`TextScramble`] resolves the characters.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScrambleOrder {
+    /// From the first character to the last.
+    #[default]
+    LeftToRight,
+    /// In a random order given by the seed.
+    Random,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        assert_eq!(TextSections::All.indices(3), vec![0, 1, 2]);
+        assert_eq!(TextSections::All.out_of_bounds(3), None);
+        assert_eq!(TextSections::from(vec![2, 5, 0]).indices(3), vec![2, 0]);
+        assert_eq!(TextSections::from(vec![2, 5, 0]).out_of_bounds(3), Some(5));
+        assert_eq!(TextSections::from(1..5).indices(3), vec![1, 2]);
+        assert_eq!(TextSections::from(1..5).out_of_bounds(3), Some(3));
+        assert_eq!(TextSections::from(4..5).out_of_bounds(3), Some(4));
+        assert_eq!(TextSections::from(1).indices(3), vec![1]);
+        assert_eq!(TextSections::from(1).out_of_bounds(3), None);
+    }
+
+    #[test]
+    fn font_size() {
+        let section = |font_size| TextSection {
+            value: String::new(),
+            style: TextStyle {
+                font_size,
+                ..Default::default()
+            },
+        };
+        let source = Text {
+            sections: vec![section(10.0), section(20.0), section(30.0)],
+            alignment: Default::default(),
+        };
+        let font_sizes =
+            |text: &Text| -> Vec<f32> { text.sections.iter().map(|s| s.style.font_size).collect() };
+        let font_size = TextFontSize {
+            target: 40.0,
+            sections: vec![2, 0].into(),
+        };
+        let mut text = source.clone();
+        font_size.apply(&source, &mut text, |_| 0.5);
+        assert_eq!(font_sizes(&text), vec![25.0, 20.0, 35.0]);
+        font_size.apply(&source, &mut text, |position| position as f32);
+        assert_eq!(font_sizes(&text), vec![40.0, 20.0, 30.0]);
+
+        let font_size = TextFontSize {
+            target: 0.0,
+            sections: TextSections::All,
+        };
+        font_size.apply(&source, &mut text, |_| 1.5);
+        assert_eq!(font_sizes(&text), vec![0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn number_format() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(12345.6), "12346");
+        assert_eq!(format.format(-0.2), "0");
+
+        let format = NumberFormat {
+            decimals: 2,
+            separator: Some(','),
+            prefix: "$".to_string(),
+            suffix: " USD".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(format.format(0.0), "$0.00 USD");
+        assert_eq!(format.format(999.999), "$1,000.00 USD");
+        assert_eq!(format.format(1234567.891), "$1,234,567.89 USD");
+        assert_eq!(format.format(-12345.0), "$-12,345.00 USD");
+
+        let format = NumberFormat {
+            custom: Some(|value| format!("{}%", value.round())),
+            ..Default::default()
+        };
+        assert_eq!(format.format(42.4), "42%");
+    }
+
+    #[test]
+    fn reveal() {
+        let reveal = TextReveal::default();
+        assert_eq!(reveal.reveal(&["héllo"], 0.0, 0.0), vec![""]);
+        assert_eq!(reveal.reveal(&["héllo"], 0.4, 0.0), vec!["hé"]);
+        assert_eq!(reveal.reveal(&["ab", "cd"], 0.75, 0.0), vec!["ab", "c"]);
+        assert_eq!(reveal.duration(&["ab", "cd"]), None);
+
+        let reveal = TextReveal {
+            unit: TextUnit::Word,
+            cursor: Some(Cursor::default()),
+            chars_per_second: Some(4.0),
+            ..Default::default()
+        };
+        assert_eq!(reveal.duration(&["ab", "cd"]), Some(1.0));
+        let sources = ["Hello  brave", " new world "];
+        assert_eq!(reveal.reveal(&sources, 0.25, 0.0), vec!["Hello_", ""]);
+        assert_eq!(reveal.reveal(&sources, 0.25, 0.75), vec!["Hello", ""]);
+        assert_eq!(
+            reveal.reveal(&sources, 0.75, 0.0),
+            vec!["Hello  brave", " new_"]
+        );
+        assert_eq!(
+            reveal.reveal(&sources, 1.0, 0.0),
+            vec!["Hello  brave", " new world "]
+        );
+
+        let reveal = TextReveal {
+            unit: TextUnit::Line,
+            ..Default::default()
+        };
+        assert_eq!(reveal.reveal(&["one\ntwo\n"], 0.7, 0.0), vec!["one\ntwo"]);
+    }
+
+    #[test]
+    fn scramble() {
+        let scramble = TextScramble {
+            chars: "#".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(scramble.scramble("abc", "Hi you", 0.0, 0, 0), "## ");
+        assert_eq!(scramble.scramble("abc", "Hi you", 0.5, 0, 0), "Hi ##");
+        assert_eq!(scramble.scramble("abc", "Hi you", 1.0, 0, 0), "Hi you");
+
+        let scramble = TextScramble {
+            order: ScrambleOrder::Random,
+            seed: 42,
+            ..Default::default()
+        };
+        let value = scramble.scramble("decode", "decode", 0.5, 3, 1);
+        assert_eq!(value, scramble.scramble("decode", "decode", 0.5, 3, 1));
+        assert!(
+            value
+                .chars()
+                .zip("decode".chars())
+                .filter(|(a, b)| a == b)
+                .count()
+                >= 3
+        );
+        assert_eq!(scramble.scramble("decode", "decode", 1.0, 3, 1), "decode");
+    }
+
+    #[test]
+    fn split() {
+        let style = TextStyle {
+            font_size: 20.0,
+            ..Default::default()
+        };
+        let mut text = Text {
+            sections: vec![
+                TextSection {
+                    value: "Hi ".to_string(),
+                    style: Default::default(),
+                },
+                TextSection {
+                    value: "to you".to_string(),
+                    style: style.clone(),
+                },
+            ],
+            alignment: Default::default(),
+        };
+        assert_eq!(split_text(&mut text, 2, TextUnit::Word), 2..2);
+        assert_eq!(split_text(&mut text, 1, TextUnit::Word), 1..3);
+        let values: Vec<_> = text.sections.iter().map(|s| s.value.as_str()).collect();
+        assert_eq!(values, vec!["Hi ", "to", " you"]);
+        assert_eq!(text.sections[2].style.font_size, 20.0);
+
+        merge_text(&mut text, 1..3);
+        assert_eq!(text.sections.len(), 2);
+        assert_eq!(text.sections[1].value, "to you");
+        assert_eq!(split_text(&mut text, 1, TextUnit::Character), 1..7);
+        merge_text(&mut text, 1..7);
+        assert_eq!(text.sections[1].value, "to you");
+
+        text.sections[0].value.clear();
+        assert_eq!(split_text(&mut text, 0, TextUnit::Word), 0..1);
+        assert_eq!(text.sections.len(), 2);
+        merge_text(&mut text, 0..1);
+        assert_eq!(text.sections.len(), 2);
+        assert_eq!(text.sections[0].value, "");
+    }
+
+    #[test]
+    fn stagger() {
+        assert_eq!(staggered(0.5, 1.0, 0.0, 3, 4), 0.5);
+        assert_eq!(staggered(0.0, 1.0, 0.5, 0, 3), 0.0);
+        assert_eq!(staggered(0.25, 1.0, 0.5, 0, 3), 0.5);
+        assert_eq!(staggered(0.25, 1.0, 0.5, 1, 3), 0.0);
+        assert_eq!(staggered(0.75, 1.0, 0.5, 1, 3), 1.0);
+        assert_eq!(staggered(0.75, 1.0, 0.5, 2, 3), 0.5);
+        assert_eq!(staggered(1.0, 1.0, 0.5, 2, 3), 1.0);
+        assert_eq!(staggered(0.5, 0.0, 0.5, 1, 3), 1.0);
+    }
+
+    #[test]
+    fn split_system() {
+        let mut world = World::default();
+        let mut stage = SystemStage::single(split_text_system);
+        let text = Text::with_section("abc", TextStyle::default(), TextAlignment::default());
+        let entity = world
+            .spawn()
+            .insert_bundle((text, SplitText::new(0, TextUnit::Character)))
+            .id();
+        let len = |world: &World| world.get::<Text>(entity).unwrap().sections.len();
+        stage.run(&mut world);
+        assert_eq!(len(&world), 3);
+        // Inserted ahead of the animation, the sections wait for it.
+        stage.run(&mut world);
+        assert_eq!(len(&world), 3);
+        world
+            .entity_mut(entity)
+            .insert(Animation::new(crate::Vars::default()));
+        stage.run(&mut world);
+        assert_eq!(len(&world), 3);
+        world.entity_mut(entity).remove::<Animation>();
+        stage.run(&mut world);
+        assert_eq!(len(&world), 1);
+        assert!(world.get::<SplitText>(entity).is_none());
+    }
+}
@@ -0,0 +1,265 @@
+use bevy::prelude::*;
+
+use crate::{blend::Layer, origin::offset_position, perspective::perspective_scale, Vars};
+
+/// The destination fields of a [`Transform`] component to animate to.
+///
+/// Fields left as `None` are never written, so a target only touches what it explicitly specifies.
+#[derive(Clone, Copy, Default)]
+pub struct TransformTarget {
+    /// Target translation.
+    pub translation: Option<Vec3>,
+    /// Target rotation, interpolated with the rotation interpolation of the animation.
+    pub rotation: Option<Quat>,
+    /// Target scale.
+    pub scale: Option<Vec3>,
+}
+impl TransformTarget {
+    /// Create a target translating to `x`, `y` and `z`.
+    pub fn from_xyz(x: f32, y: f32, z: f32) -> Self {
+        Self::from_translation(Vec3::new(x, y, z))
+    }
+    /// Create a target translating to `translation`.
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation: Some(translation),
+            ..Default::default()
+        }
+    }
+    /// Create a target rotating to `rotation`.
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Self {
+            rotation: Some(rotation),
+            ..Default::default()
+        }
+    }
+    /// Create a target scaling to `scale`.
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale: Some(scale),
+            ..Default::default()
+        }
+    }
+}
+impl From<Transform> for TransformTarget {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: Some(transform.translation),
+            rotation: Some(transform.rotation),
+            scale: Some(transform.scale),
+        }
+    }
+}
+
+/// The properties of the transform written by the channels of an animation.
+#[derive(Clone, Copy)]
+pub(crate) struct Targeted {
+    translation: bool,
+    rotation: bool,
+    scale: bool,
+}
+impl Targeted {
+    /// `styled` is whether the transform origin and shake move the entity through its style.
+    pub(crate) fn new(vars: &Vars, styled: bool) -> Self {
+        let target = vars.transform.unwrap_or_default();
+        let moved = vars.transform_origin.is_some() || vars.shake.is_some();
+        Self {
+            translation: target.translation.is_some() || (moved && !styled),
+            rotation: target.rotation.is_some()
+                || vars.transform_rotation.is_some()
+                || vars.euler_rotation.is_some()
+                || vars.shake.is_some(),
+            scale: target.scale.is_some() || vars.perspective.is_some(),
+        }
+    }
+}
+
+/// Writes the transform channels of `vars` at `delta`, from the `source` transform.
+///
+/// `shake` is the offset in pixels and the rotation in degrees of the shake, and `size` the size of the
+/// node or sprite. Returns the offset in pixels the transform origin and the shake move the entity by.
+pub(crate) fn apply(
+    vars: &Vars,
+    source: &Transform,
+    transform: &mut Transform,
+    delta: f32,
+    shake: Option<(Vec2, f32)>,
+    size: Option<Vec2>,
+) -> Option<Vec2> {
+    let target = vars.transform.unwrap_or_default();
+    if let Some(translation) = target.translation {
+        transform.translation = source.translation.lerp(translation, delta);
+    }
+    if let Some(rotation) = target.rotation {
+        let interpolation = vars.rotation_interpolation;
+        transform.rotation = interpolation.interpolate(source.rotation, rotation, delta);
+    }
+    if let Some(scale) = target.scale {
+        transform.scale = source.scale.lerp(scale, delta);
+    }
+    if let Some(ref target) = vars.transform_rotation {
+        let source_angle = source.rotation.to_axis_angle().1;
+        let target_angle = target.degree.to_radians();
+        let delta_angle = source_angle + (target_angle - source_angle) * delta;
+        transform.rotation = Quat::from_axis_angle(target.axis, delta_angle);
+    }
+    if let Some(ref target) = vars.euler_rotation {
+        transform.rotation = source.rotation * target.rotation(delta);
+    }
+    if let Some((_, degrees)) = shake {
+        let rotated = target.rotation.is_some()
+            || vars.transform_rotation.is_some()
+            || vars.euler_rotation.is_some();
+        let base = if rotated {
+            transform.rotation
+        } else {
+            source.rotation
+        };
+        transform.rotation = base * Quat::from_rotation_z(-degrees.to_radians());
+    }
+    if let (Some(distance), Some(size)) = (vars.perspective, size) {
+        let base = if target.scale.is_some() {
+            transform.scale
+        } else {
+            source.scale
+        };
+        transform.scale = base * perspective_scale(distance, size, transform.rotation);
+    }
+    let origin = vars
+        .transform_origin
+        .zip(size)
+        .map(|(origin, size)| origin.compensation(size, transform.rotation, transform.scale));
+    if origin.is_none() && shake.is_none() {
+        return None;
+    }
+    Some(origin.unwrap_or_default() + shake.map_or(Vec2::ZERO, |(translation, _)| translation))
+}
+
+/// Moves a UI node by `offset` pixels through the position of its `style`, or any other entity through its
+/// translation, from their source unless another channel animates them.
+///
+/// Each axis of the position is offset through the side it's anchored to, percentages by a percentage of
+/// the `parent` size.
+pub(crate) fn translate(
+    vars: &Vars,
+    offset: Vec2,
+    source: &Transform,
+    transform: &mut Transform,
+    style: Option<(&Style, &mut Style)>,
+    parent: Vec2,
+) {
+    match style {
+        Some((source_style, style)) => {
+            let animated = vars.style.as_ref().and_then(|target| target.position);
+            let base = if animated.is_some() {
+                style.position
+            } else {
+                source_style.position
+            };
+            style.position = offset_position(base, offset, parent);
+        }
+        None => {
+            let animated = vars.transform.and_then(|target| target.translation);
+            let base = if animated.is_some() {
+                transform.translation
+            } else {
+                source.translation
+            };
+            transform.translation = base + offset.extend(0.0);
+        }
+    }
+}
+
+/// Replaces the `targeted` properties of `source` by their base values in `layer`.
+pub(crate) fn base(layer: &mut Layer, targeted: Targeted, source: &mut Transform) {
+    if targeted.translation {
+        source.translation = layer.base(Some("translation"), source.translation);
+    }
+    if targeted.rotation {
+        source.rotation = layer.base(Some("rotation"), source.rotation);
+    }
+    if targeted.scale {
+        source.scale = layer.base(Some("scale"), source.scale);
+    }
+}
+
+/// Adds the change from `source` to `transform` of the `targeted` properties onto their base values in `layer`.
+///
+/// `live` is the transform before the animation wrote to it.
+pub(crate) fn add(
+    layer: &mut Layer,
+    targeted: Targeted,
+    live: &Transform,
+    source: &Transform,
+    transform: &mut Transform,
+) {
+    if targeted.translation {
+        transform.translation = layer.add(
+            Some("translation"),
+            live.translation,
+            &source.translation,
+            &transform.translation,
+        );
+    }
+    if targeted.rotation {
+        transform.rotation = layer.add(
+            Some("rotation"),
+            live.rotation,
+            &source.rotation,
+            &transform.rotation,
+        );
+    }
+    if targeted.scale {
+        transform.scale = layer.add(Some("scale"), live.scale, &source.scale, &transform.scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Blend, Layers};
+
+    #[test]
+    fn partial() {
+        let source = Transform::from_xyz(100.0, 0.0, 0.0);
+        let mut transform = source;
+        let vars = Vars {
+            transform: Some(TransformTarget::from_scale(Vec3::splat(2.0))),
+            ..Default::default()
+        };
+        apply(&vars, &source, &mut transform, 0.5, None, None);
+        assert_eq!(transform.translation, source.translation);
+        assert_eq!(transform.scale, Vec3::splat(1.5));
+    }
+
+    #[test]
+    fn additive() {
+        let mut layers = Layers::default();
+        let entity = Entity::from_raw(0);
+        let mut transform = Transform::from_xyz(100.0, 0.0, 0.0);
+        let vars = Vars {
+            transform: Some(TransformTarget::from_scale(Vec3::splat(1.5))),
+            blend: Blend::Additive,
+            ..Default::default()
+        };
+        let targeted = Targeted::new(&vars, false);
+        let sources = [0, 1].map(|id| {
+            let mut source = transform;
+            base(
+                &mut Layer::new::<Transform>(&mut layers, entity, id),
+                targeted,
+                &mut source,
+            );
+            source
+        });
+        for (id, source) in sources.iter().enumerate() {
+            let live = transform;
+            apply(&vars, source, &mut transform, 1.0, None, None);
+            let mut layer = Layer::new::<Transform>(&mut layers, entity, id as u64);
+            add(&mut layer, targeted, &live, source, &mut transform);
+        }
+        assert_eq!(transform.translation, Vec3::new(100.0, 0.0, 0.0));
+        assert_eq!(transform.rotation, Quat::IDENTITY);
+        assert!(transform.scale.abs_diff_eq(Vec3::splat(2.0), 1e-5));
+    }
+}